@@ -55,11 +55,11 @@ where
     else {
         let total: u128 = total.to_u128().unwrap();
         if (EXBI..ZEBI).contains(&total) {
-            return format!("{:.2} E", total.to_f64().unwrap() / EXBI as f64);
+            format!("{:.2} E", total.to_f64().unwrap() / EXBI as f64)
         } else if (ZEBI..YOBI).contains(&total) {
-            return format!("{:.2} Z", total.to_f64().unwrap() / ZEBI as f64);
+            format!("{:.2} Z", total.to_f64().unwrap() / ZEBI as f64)
         } else if YOBI <= total {
-            return format!("{:.2} Y", total.to_f64().unwrap() / YOBI as f64);
+            format!("{:.2} Y", total.to_f64().unwrap() / YOBI as f64)
         } else {
             unreachable!()
         }
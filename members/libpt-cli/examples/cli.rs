@@ -1,7 +1,7 @@
 use clap::Parser;
-use libpt_cli::args::VerbosityLevel;
+use libpt_cli::args::LogArgs;
 use libpt_cli::printing;
-use libpt_log::{debug, Logger};
+use libpt_log::debug;
 
 /// This is the help
 ///
@@ -10,7 +10,7 @@ use libpt_log::{debug, Logger};
 struct Cli {
     // already has documentation
     #[command(flatten)]
-    verbosity: VerbosityLevel,
+    log: LogArgs,
 
     /// texts to be echoed
     #[arg(required = true)]
@@ -23,9 +23,12 @@ struct Cli {
 
 fn main() {
     let cli = Cli::parse();
-    let _logger = Logger::builder().set_level(cli.verbosity.level()).build();
+    let _logger = cli.log.logger_builder().build();
 
-    debug!("logger initialized with level: {}", cli.verbosity.level());
+    debug!(
+        "logger initialized with level: {}",
+        cli.log.verbosity.level()
+    );
 
     if !cli.machine {
         let text = cli.text.join(" ");
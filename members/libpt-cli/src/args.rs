@@ -1,7 +1,12 @@
 //! Utilities for parsing options and arguments on the start of a CLI application
 
-use clap::Parser;
-use libpt_log::Level;
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
+
+use clap::{builder::TypedValueParser, ColorChoice, Parser};
+use libpt_log::{Level, LevelFilter, LogFormat, Logger, LoggerBuilder};
 #[cfg(feature = "log")]
 use log;
 use serde::{Deserialize, Serialize};
@@ -79,7 +84,7 @@ Author: {author-with-newline}
 ///
 /// ```no_run
 /// use libpt_cli::args::VerbosityLevel;
-/// use libpt_log::Level;
+/// use libpt_log::{Level, LevelFilter};
 /// use clap::Parser;
 ///
 /// # #[derive(Parser, Debug)]
@@ -91,11 +96,14 @@ Author: {author-with-newline}
 /// fn main() {
 ///     let opts = Opts::parse();
 ///
-///     // Level might be None if the user wants no output at all.
 ///     // for the 'tracing' level:
 ///     let level: Level = opts.verbose.level();
+///     // OFF if the user wants no output at all:
+///     let filter: LevelFilter = opts.verbose.level_filter();
 /// }
 /// ```
+///
+/// The default can be taken from an environment variable, see [`Self::with_env`].
 #[derive(Parser, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct VerbosityLevel {
     /// make the output more verbose
@@ -120,26 +128,160 @@ pub struct VerbosityLevel {
         conflicts_with = "verbose",
     )]
     quiet: u8,
+
+    /// verbosity from the environment, used if neither verbose nor quiet was set
+    ///
+    /// Positive values are like verbose, negative values like quiet.
+    #[arg(skip)]
+    #[serde(skip)]
+    env: Option<i8>,
+}
+
+/// Where the value of a [`VerbosityLevel`] came from, see [`VerbosityLevel::source`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerbositySource {
+    /// neither flags nor the environment changed it
+    Default,
+    /// the environment variable given to [`VerbosityLevel::with_env`]
+    Env,
+    /// the `-v` or `-q` flags
+    Flags,
+}
+
+/// The environment variable given to [`VerbosityLevel::with_env`] has an invalid value
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid verbosity in ${var}: {value:?}")]
+pub struct VerbosityEnvError {
+    /// name of the environment variable
+    pub var: String,
+    /// the invalid value
+    pub value: String,
 }
 
 impl VerbosityLevel {
-    /// true only if no verbose and no quiet was set (user is using defaults)
+    /// true if the verbosity is not the default, see [`Self::source`] for where it came from
     #[inline]
     #[must_use]
-    #[allow(clippy::missing_const_for_fn)] // the values of self can change
     pub fn changed(&self) -> bool {
-        self.verbose != 0 || self.quiet != 0
+        self.source() != VerbositySource::Default
+    }
+
+    /// where the verbosity came from
+    ///
+    /// Explicit `-v` or `-q` flags take precedence over the environment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use libpt_cli::args::{VerbosityLevel, VerbositySource};
+    ///
+    /// use libpt_log::LevelFilter;
+    ///
+    /// assert_eq!(VerbosityLevel::INFO.source(), VerbositySource::Default);
+    /// assert_eq!(VerbosityLevel::DEBUG.source(), VerbositySource::Flags);
+    ///
+    /// std::env::set_var("EXAMPLE_VERBOSITY", "-3");
+    /// let from_env = VerbosityLevel::INFO.with_env("EXAMPLE_VERBOSITY").unwrap();
+    /// assert_eq!(from_env.source(), VerbositySource::Env);
+    /// assert_eq!(from_env.level_filter(), LevelFilter::OFF);
+    ///
+    /// // flags take precedence
+    /// let from_flags = VerbosityLevel::DEBUG.with_env("EXAMPLE_VERBOSITY").unwrap();
+    /// assert_eq!(from_flags.level_filter(), LevelFilter::DEBUG);
+    ///
+    /// std::env::set_var("EXAMPLE_VERBOSITY", "loud");
+    /// assert!(VerbosityLevel::INFO.with_env("EXAMPLE_VERBOSITY").is_err());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn source(&self) -> VerbositySource {
+        if self.verbose != 0 || self.quiet != 0 {
+            VerbositySource::Flags
+        } else if self.env.is_some() {
+            VerbositySource::Env
+        } else {
+            VerbositySource::Default
+        }
+    }
+
+    /// take the verbosity from the environment variable `var`, unless flags were given
+    ///
+    /// The variable can hold a level like `debug`, `warn` or `off`, or a signed number that is
+    /// counted like the flags: `2` is like `-vv` and `-1` is like `-q`. If the variable is not
+    /// set, nothing changes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use libpt_cli::args::VerbosityLevel;
+    /// use clap::Parser;
+    ///
+    /// # #[derive(Parser, Debug)]
+    /// # pub struct Opts {
+    /// #     #[command(flatten)]
+    /// #     pub verbose: VerbosityLevel,
+    /// # }
+    /// // MYAPP_VERBOSITY=debug ./myapp
+    /// let opts = Opts::parse();
+    /// let verbosity = opts.verbose.with_env("MYAPP_VERBOSITY").unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if the variable is set to something that is neither a level nor a number.
+    pub fn with_env(mut self, var: &str) -> Result<Self, VerbosityEnvError> {
+        let Some(value) = std::env::var_os(var) else {
+            return Ok(self);
+        };
+        let error = || VerbosityEnvError {
+            var: var.to_string(),
+            value: value.to_string_lossy().into_owned(),
+        };
+        let value = value.to_str().ok_or_else(error)?.trim();
+        let offset = match value.to_ascii_lowercase().as_str() {
+            "off" => -3,
+            "error" => -2,
+            "warn" => -1,
+            "info" => 0,
+            "debug" => 1,
+            "trace" => 2,
+            number => number
+                .strip_prefix('+')
+                .unwrap_or(number)
+                .parse::<i8>()
+                .map_err(|_| error())?
+                .clamp(-10, 10),
+        };
+        self.env = Some(offset);
+        Ok(self)
+    }
+
+    /// the verbose and quiet counts, from the flags or else from the environment
+    #[inline]
+    #[must_use]
+    fn counts(&self) -> (u8, u8) {
+        match (self.source(), self.env) {
+            (VerbositySource::Env, Some(offset)) => {
+                (offset.max(0).unsigned_abs(), offset.min(0).unsigned_abs())
+            }
+            _ => (self.verbose.min(10), self.quiet.min(10)),
+        }
     }
+
     #[inline]
     #[must_use]
     fn value(&self) -> u8 {
+        let (verbose, quiet) = self.counts();
         Self::level_value(Level::INFO)
-            .saturating_sub((self.quiet).min(10))
-            .saturating_add((self.verbose).min(10))
+            .saturating_sub(quiet)
+            .saturating_add(verbose)
     }
 
     /// get the [Level] for that [`VerbosityLevel`]
     ///
+    /// This is at least [`Level::ERROR`], even if the user wants no output at all. Use
+    /// [`Self::level_filter`] to respect that.
+    ///
     /// # Examples
     ///
     /// ```
@@ -170,11 +312,35 @@ impl VerbosityLevel {
         }
     }
 
+    /// get the [`LevelFilter`] for that [`VerbosityLevel`]
+    ///
+    /// Unlike [`Self::level`], this is [`LevelFilter::OFF`] if the user wants no output at all
+    /// (`-qqq`). The [`LevelFilter`] can be given to [`LoggerBuilder::set_level`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use libpt_log::{Level, LevelFilter};
+    /// use libpt_cli::args::VerbosityLevel;
+    ///
+    /// assert_eq!(VerbosityLevel::ERROR.level_filter(), LevelFilter::ERROR);
+    /// assert_eq!(VerbosityLevel::OFF.level_filter(), LevelFilter::OFF);
+    /// assert_eq!(VerbosityLevel::OFF.level(), Level::ERROR);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn level_filter(&self) -> LevelFilter {
+        let (verbose, quiet) = self.counts();
+        if quiet > Self::level_value(Level::INFO) + verbose {
+            LevelFilter::OFF
+        } else {
+            LevelFilter::from_level(self.level())
+        }
+    }
+
     /// get the [`log::Level`] for that `VerbosityLevel`
     ///
     /// This is the method for the [log] crate, which I use less often.
-    ///
-    /// [None] means that absolutely no output is wanted (completely quiet)
     #[inline]
     #[must_use]
     #[cfg(feature = "log")]
@@ -212,6 +378,7 @@ impl VerbosityLevel {
     pub const TRACE: Self = Self {
         verbose: 2,
         quiet: 0,
+        env: None,
     };
     /// # Examples
     ///
@@ -225,6 +392,7 @@ impl VerbosityLevel {
     pub const DEBUG: Self = Self {
         verbose: 1,
         quiet: 0,
+        env: None,
     };
     /// # Examples
     ///
@@ -238,6 +406,7 @@ impl VerbosityLevel {
     pub const INFO: Self = Self {
         verbose: 0,
         quiet: 0,
+        env: None,
     };
     /// # Examples
     ///
@@ -251,6 +420,7 @@ impl VerbosityLevel {
     pub const WARN: Self = Self {
         verbose: 0,
         quiet: 1,
+        env: None,
     };
     /// # Examples
     ///
@@ -264,6 +434,21 @@ impl VerbosityLevel {
     pub const ERROR: Self = Self {
         verbose: 0,
         quiet: 2,
+        env: None,
+    };
+    /// # Examples
+    ///
+    /// ```
+    /// use libpt_log::LevelFilter; // reexport: tracing
+    /// use libpt_cli::args::VerbosityLevel;
+    ///
+    /// let verbosity_level = VerbosityLevel::OFF;
+    /// assert_eq!(verbosity_level.level_filter(), LevelFilter::OFF);
+    /// ```
+    pub const OFF: Self = Self {
+        verbose: 0,
+        quiet: 3,
+        env: None,
     };
 }
 
@@ -279,3 +464,110 @@ impl Default for VerbosityLevel {
         Self::INFO
     }
 }
+
+/// The common logging flags of a CLI application
+///
+/// This bundles the [`VerbosityLevel`] with flags for the logfile, the [`LogFormat`] and colors,
+/// so that all applications have the same logging flags:
+///
+/// * `-v`/`-q` – see [`VerbosityLevel`]
+/// * `--log-file <FILE>` – also log to `FILE`, which may contain the placeholders of
+///   [`LoggerBuilder::logfile_template`]
+/// * `--log-dir <DIR>` – also log to a file in `DIR`, named like
+///   [`DEFAULT_LOGFILE_TEMPLATE`](libpt_log::DEFAULT_LOGFILE_TEMPLATE)
+/// * `--log-format <FORMAT>` – the [`LogFormat`] of the terminal and the logfile
+/// * `--color <WHEN>` – use colors `always`, `never` or, by default, if the stdout is a terminal
+///   and `NO_COLOR` is not set (`auto`)
+///
+/// # Example
+///
+/// ```no_run
+/// use libpt_cli::args::LogArgs;
+/// use libpt_log::info;
+/// use clap::Parser;
+///
+/// #[derive(Parser, Debug)]
+/// pub struct Opts {
+///     #[command(flatten)]
+///     pub log: LogArgs,
+///     #[arg(short, long)]
+///     pub mynum: usize,
+/// }
+///
+/// fn main() {
+///     let opts = Opts::parse();
+///     let _logger = opts.log.logger_builder().build().unwrap();
+///     info!("logging as requested");
+/// }
+/// ```
+#[derive(Parser, Debug, Clone, PartialEq, Eq, Default)]
+pub struct LogArgs {
+    /// how verbose the logs are
+    #[command(flatten)]
+    pub verbosity: VerbosityLevel,
+
+    /// also write the logs to this file
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "log_dir")]
+    pub log_file: Option<PathBuf>,
+
+    /// also write the logs to a file in this directory
+    #[arg(long, global = true, value_name = "DIR")]
+    pub log_dir: Option<PathBuf>,
+
+    /// format of the logs
+    #[arg(long, global = true, value_name = "FORMAT", default_value_t, value_parser = log_format_parser())]
+    pub log_format: LogFormat,
+
+    /// when to use colors in the logs
+    #[arg(long, global = true, value_name = "WHEN", value_enum, default_value_t)]
+    pub color: ColorChoice,
+}
+
+impl LogArgs {
+    /// create a [`LoggerBuilder`] configured by these flags
+    ///
+    /// More settings can be changed on the returned [`LoggerBuilder`] before it is built.
+    #[must_use]
+    pub fn logger_builder(&self) -> LoggerBuilder {
+        let mut builder = Logger::builder()
+            .set_level(self.verbosity.level_filter())
+            .terminal_format(self.log_format)
+            .file_format(self.log_format)
+            .ansi(self.ansi());
+        if let Some(file) = &self.log_file {
+            let dir = file
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."));
+            builder = builder.log_to_file(true).log_dir(dir.to_path_buf());
+            if let Some(name) = file.file_name() {
+                builder = builder.logfile_template(name.to_string_lossy());
+            }
+        } else if let Some(dir) = &self.log_dir {
+            builder = builder.log_to_file(true).log_dir(dir.clone());
+        }
+        builder
+    }
+
+    /// decide if colors are used, following the `--color` flag
+    fn ansi(&self) -> bool {
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+        }
+    }
+}
+
+/// parse a [`LogFormat`] and show the possible values in the help
+fn log_format_parser() -> impl TypedValueParser<Value = LogFormat> {
+    clap::builder::PossibleValuesParser::new(LogFormat::ALL.iter().map(|format| format.name())).map(
+        |format| {
+            format
+                .parse::<LogFormat>()
+                .expect("only possible values are parsed")
+        },
+    )
+}
@@ -178,8 +178,7 @@ where
                     .split_whitespace()
                     .map(str::to_lowercase)
                     .next()
-                    .unwrap()
-                    .to_string(),
+                    .unwrap(),
             );
         }
         trace!("commands: {buf:?}");
@@ -209,7 +208,7 @@ where
 
         trace!("\nmatches: {matches:#?}");
         if matches.len() == 1 {
-            Some(matches[0].to_string())
+            Some(matches[0].clone())
         } else {
             None
         }
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use libpt_cli::args::LogArgs;
+use libpt_log::{Level, LogFormat, Logger, LoggerBuilder};
+
+#[derive(Parser, Debug)]
+struct Cli {
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+fn builder(args: &[&str]) -> LoggerBuilder {
+    Cli::try_parse_from(std::iter::once("app").chain(args.iter().copied()))
+        .unwrap()
+        .log
+        .logger_builder()
+}
+
+fn expected() -> LoggerBuilder {
+    Logger::builder().set_level(Level::INFO).ansi(false)
+}
+
+#[test]
+fn log_file_is_split() {
+    assert_eq!(
+        builder(&["--color", "never", "--log-file", "logs/{date}-app.log"]),
+        expected()
+            .log_to_file(true)
+            .log_dir(PathBuf::from("logs"))
+            .logfile_template("{date}-app.log")
+    );
+}
+
+#[test]
+fn bare_log_file_is_in_working_dir() {
+    assert_eq!(
+        builder(&["--color", "never", "--log-file", "app.log"]),
+        expected()
+            .log_to_file(true)
+            .log_dir(PathBuf::from("."))
+            .logfile_template("app.log")
+    );
+}
+
+#[test]
+fn log_dir_keeps_default_template() {
+    assert_eq!(
+        builder(&["--color", "never", "--log-dir", "logs"]),
+        expected().log_to_file(true).log_dir(PathBuf::from("logs"))
+    );
+}
+
+#[test]
+fn log_file_conflicts_with_log_dir() {
+    let err =
+        Cli::try_parse_from(["app", "--log-file", "app.log", "--log-dir", "logs"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+}
+
+#[test]
+fn verbosity_and_format() {
+    assert_eq!(
+        builder(&["--color", "never", "-vv", "--log-format", "json"]),
+        expected()
+            .set_level(Level::TRACE)
+            .terminal_format(LogFormat::Json)
+            .file_format(LogFormat::Json)
+    );
+}
+
+#[test]
+fn color_always_and_never() {
+    assert_eq!(builder(&["--color", "always"]), expected().ansi(true));
+    assert_eq!(builder(&["--color", "never"]), expected());
+}
@@ -0,0 +1,22 @@
+use clap::Parser;
+use libpt_cli::args::LogArgs;
+use libpt_log::{Level, Logger};
+
+#[derive(Parser, Debug)]
+struct Cli {
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+// NO_COLOR is shared by the whole process, so this test has its own file
+#[test]
+fn auto_color_respects_no_color() {
+    std::env::set_var("NO_COLOR", "1");
+    for args in [&["app"][..], &["app", "--color", "auto"]] {
+        let builder = Cli::try_parse_from(args).unwrap().log.logger_builder();
+        assert_eq!(
+            builder,
+            Logger::builder().set_level(Level::INFO).ansi(false)
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! # Bridge for the `log` crate in [`pt-log`](crate)
+//!
+//! Many crates emit their records through the [`log`](tracing_log::log) facade instead of
+//! [`tracing`]. This module turns those records into [`tracing`] events, so that they reach the
+//! same sinks and filters as all other events.
+
+use std::sync::{Mutex, PoisonError};
+
+use tracing::level_filters::LevelFilter;
+use tracing_log::{
+    log::{self, SetLoggerError},
+    AsLog, LogTracer,
+};
+
+/// whether the [`LogTracer`] was installed by us
+static INSTALLED: Mutex<bool> = Mutex::new(false);
+
+/// install the [`LogTracer`] as the logger of the `log` crate, unless it already is
+///
+/// # Errors
+///
+/// Fails if another logger was installed for the `log` crate.
+#[allow(clippy::significant_drop_tightening)] // don't let two threads install it at once
+pub fn install() -> Result<(), SetLoggerError> {
+    let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+    if !*installed {
+        LogTracer::init()?;
+        *installed = true;
+    }
+    Ok(())
+}
+
+/// let the `log` crate skip records that no subscriber is interested in
+///
+/// This needs to be done whenever the filters change, otherwise records that just became
+/// interesting would still be skipped.
+pub fn update_max_level() {
+    if *INSTALLED.lock().unwrap_or_else(PoisonError::into_inner) {
+        log::set_max_level(LevelFilter::current().as_log());
+    }
+}
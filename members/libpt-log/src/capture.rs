@@ -0,0 +1,159 @@
+//! # Capturing logs in memory
+//!
+//! This module helps testing that code logs the right things. A [`Capture`] records the events
+//! of the current thread in memory, where they can be inspected, instead of formatting them.
+//!
+//! The capture is scoped: it is only active for the thread that started it, until it is dropped.
+//! Tests running in parallel threads therefore only ever see their own events, and a global
+//! [Logger](crate::Logger) is not needed.
+//!
+//! ## Examples
+//!
+//! ```
+//! use libpt_log::{capture::Capture, info, warn, Level};
+//!
+//! let capture = Capture::start();
+//! info!(user = "alice", "logged in");
+//! warn!("disk almost full");
+//!
+//! assert!(capture.contains(Level::WARN, "disk almost full"));
+//! let events = capture.events();
+//! assert_eq!(events[0].message, "logged in");
+//! assert_eq!(events[0].fields["user"], "alice");
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use tracing::{
+    field::{Field, Visit},
+    subscriber::DefaultGuard,
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, layer::SubscriberExt as _, Layer};
+
+/// An event recorded by a [`Capture`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedEvent {
+    /// the level of the event
+    pub level: Level,
+    /// the target of the event, usually the module path it was logged in
+    pub target: String,
+    /// the message of the event, empty if it has none
+    pub message: String,
+    /// all other fields of the event, formatted as text
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Collects the fields of an event into a [`CapturedEvent`]
+struct CaptureVisitor<'a>(&'a mut CapturedEvent);
+
+impl Visit for CaptureVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{value:?}"));
+    }
+}
+
+impl CaptureVisitor<'_> {
+    fn record(&mut self, field: &Field, value: String) {
+        if field.name() == "message" {
+            self.0.message = value;
+        } else {
+            self.0.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+/// A [Layer] that records events into a shared list
+#[derive(Debug, Clone, Default)]
+struct CaptureLayer {
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut captured = CapturedEvent {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            message: String::new(),
+            fields: BTreeMap::new(),
+        };
+        event.record(&mut CaptureVisitor(&mut captured));
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(captured);
+    }
+}
+
+/// Records the events of the current thread in memory while it exists
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug)]
+pub struct Capture {
+    /// the recorded events, shared with the [`CaptureLayer`]
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+    /// restores the previous subscriber of this thread when dropped
+    _guard: DefaultGuard,
+}
+
+impl Capture {
+    /// start recording all events of the current thread
+    ///
+    /// Events of all levels are recorded, including [TRACE](Level::TRACE). Events logged in
+    /// other threads, even ones spawned by this thread, are not recorded.
+    #[must_use]
+    pub fn start() -> Self {
+        Self::start_with_level(Level::TRACE)
+    }
+
+    /// start recording the events of the current thread up to `level`
+    ///
+    /// Events that are more verbose than `level` are not recorded, just like with
+    /// [`LoggerBuilder::set_level`](crate::LoggerBuilder::set_level).
+    #[must_use]
+    pub fn start_with_level(level: Level) -> Self {
+        let layer = CaptureLayer::default();
+        let events = layer.events.clone();
+        let subscriber = tracing_subscriber::registry()
+            .with(layer.with_filter(tracing::level_filters::LevelFilter::from_level(level)));
+        Self {
+            events,
+            _guard: tracing::subscriber::set_default(subscriber),
+        }
+    }
+
+    /// all events recorded so far, oldest first
+    #[must_use]
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// check if an event with the given `level` was recorded whose message contains `message`
+    #[must_use]
+    pub fn contains(&self, level: Level, message: &str) -> bool {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .any(|event| event.level == level && event.message.contains(message))
+    }
+
+    /// forget all events recorded so far
+    pub fn clear(&self) {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
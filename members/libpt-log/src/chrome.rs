@@ -0,0 +1,22 @@
+//! # Chrome trace export for [`pt-log`](crate)
+//!
+//! This module writes spans as [Chrome trace events](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+//! so that a run can be opened in [Perfetto](https://ui.perfetto.dev) or `chrome://tracing`,
+//! with one timeline per thread. See
+//! [`LoggerBuilder::chrome_trace`](crate::LoggerBuilder::chrome_trace).
+
+use std::{fs::File, io, path::Path};
+
+use tracing_chrome::{ChromeLayer, ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::Registry;
+
+/// create the layer writing the trace to the file at `path`, and the guard that finishes the file
+///
+/// The fields of the spans and events are included as their `args`.
+pub fn layer(path: &Path) -> io::Result<(ChromeLayer<Registry>, FlushGuard)> {
+    let file = File::create(path)?;
+    Ok(ChromeLayerBuilder::new()
+        .writer(file)
+        .include_args(true)
+        .build())
+}
@@ -0,0 +1,221 @@
+//! # Configuration files for [`pt-log`](crate)
+//!
+//! This module lets the users of a program configure its logging in the same config file as
+//! everything else. A [`LoggerConfig`] can be deserialized with [`serde`], for example from a
+//! `[logging]` section of a TOML file, and turned into a [`LoggerBuilder`]:
+//!
+//! ```toml
+//! [logging]
+//! level = "info"
+//! filter = "hyper=warn"
+//! display_target = true
+//!
+//! [logging.file]
+//! enabled = true
+//! dir = "/var/log/myapp"
+//! level = "trace"
+//! format = "json"
+//! rotation = "daily"
+//! max_files = 7
+//! ```
+//!
+//! Everything that is left out keeps the value of the [`LoggerBuilder`] the config is applied to,
+//! see [`LoggerBuilder::apply_config`].
+
+use std::{path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::{Compression, Level, LevelFilter, LogFormat, LoggerBuilder, Rotation, TerminalStream};
+
+/// The logging configuration of a program, as found in a config file
+///
+/// See the [module documentation](self) for an example. Unknown fields are rejected, so that
+/// typos don't go unnoticed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggerConfig {
+    /// the lowest loglevel to be displayed, like `"info"` or `"off"`, see
+    /// [`LoggerBuilder::set_level`]
+    #[serde(deserialize_with = "deserialize_level")]
+    pub level: Option<LevelFilter>,
+    /// filter directives like `"mycrate=debug,hyper=warn"`, see [`LoggerBuilder::filter`]
+    pub filter: Option<String>,
+    /// an environment variable with more filter directives, see [`LoggerBuilder::filter_env`]
+    pub filter_env: Option<String>,
+    /// see [`LoggerBuilder::terminal_format`]
+    pub format: Option<LogFormat>,
+    /// see [`LoggerBuilder::terminal_stream`]
+    pub stream: Option<TerminalStream>,
+    /// see [`LoggerBuilder::ansi`]
+    pub ansi: Option<bool>,
+    /// see [`LoggerBuilder::display_filename`]
+    pub display_filename: Option<bool>,
+    /// see [`LoggerBuilder::display_time`]
+    pub display_time: Option<bool>,
+    /// see [`LoggerBuilder::display_level`]
+    pub display_level: Option<bool>,
+    /// see [`LoggerBuilder::display_target`]
+    pub display_target: Option<bool>,
+    /// see [`LoggerBuilder::display_thread_ids`]
+    pub display_thread_ids: Option<bool>,
+    /// see [`LoggerBuilder::display_thread_names`]
+    pub display_thread_names: Option<bool>,
+    /// see [`LoggerBuilder::display_line_number`]
+    pub display_line_number: Option<bool>,
+    /// see [`LoggerBuilder::pretty`]
+    pub pretty: Option<bool>,
+    /// see [`LoggerBuilder::uptime`]
+    pub uptime: Option<bool>,
+    /// the logfile
+    pub file: FileConfig,
+}
+
+/// The configuration of the logfile, part of a [`LoggerConfig`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    /// log to a file, see [`LoggerBuilder::log_to_file`]
+    pub enabled: Option<bool>,
+    /// see [`LoggerBuilder::log_dir`]
+    pub dir: Option<PathBuf>,
+    /// see [`LoggerBuilder::logfile_template`]
+    pub template: Option<String>,
+    /// see [`LoggerBuilder::file_level`]
+    #[serde(deserialize_with = "deserialize_level")]
+    pub level: Option<LevelFilter>,
+    /// see [`LoggerBuilder::file_format`]
+    pub format: Option<LogFormat>,
+    /// see [`LoggerBuilder::file_ansi`]
+    pub ansi: Option<bool>,
+    /// see [`LoggerBuilder::file_display_target`]
+    pub display_target: Option<bool>,
+    /// see [`LoggerBuilder::file_display_thread_ids`]
+    pub display_thread_ids: Option<bool>,
+    /// see [`LoggerBuilder::append`]
+    pub append: Option<bool>,
+    /// see [`LoggerBuilder::non_blocking`]
+    pub non_blocking: Option<bool>,
+    /// like `"daily"` or `{ size = { max_bytes = 1000000, keep = 3 } }`, see
+    /// [`LoggerBuilder::rotation`]
+    pub rotation: Option<Rotation>,
+    /// see [`LoggerBuilder::compression`]
+    pub compression: Option<Compression>,
+    /// see [`LoggerBuilder::max_logfiles`]
+    pub max_files: Option<usize>,
+    /// the maximum age of logfiles in seconds, see [`LoggerBuilder::max_logfile_age`]
+    pub max_age_secs: Option<u64>,
+}
+
+/// deserialize a [`LevelFilter`] from its name, like `"info"` or `"off"`, or its number, like `"3"`
+fn deserialize_level<'de, D>(deserializer: D) -> Result<Option<LevelFilter>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|level| level.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// deserialize the threshold of [`TerminalStream::Split`]
+pub(crate) fn deserialize_threshold<'de, D>(deserializer: D) -> Result<Level, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+impl LoggerBuilder {
+    /// create a [`LoggerBuilder`] from a [`LoggerConfig`]
+    ///
+    /// Everything the config leaves out has its default value.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{config::LoggerConfig, LoggerBuilder};
+    /// let config = LoggerConfig {
+    ///     display_target: Some(true),
+    ///     ..Default::default()
+    /// };
+    /// let builder = LoggerBuilder::from_config(&config);
+    /// ```
+    #[must_use]
+    pub fn from_config(config: &LoggerConfig) -> Self {
+        Self::default().apply_config(config)
+    }
+
+    /// override the settings of this [`LoggerBuilder`] with those given in a [`LoggerConfig`]
+    ///
+    /// Everything the config leaves out keeps its current value. This way, a program can set its
+    /// own defaults and let the user change them.
+    #[must_use]
+    pub fn apply_config(mut self, config: &LoggerConfig) -> Self {
+        let file = &config.file;
+        if let Some(level) = config.level {
+            self = self.set_level(level);
+        }
+        if let Some(filter) = &config.filter {
+            self = self.filter(filter.as_str());
+        }
+        if let Some(var) = &config.filter_env {
+            self = self.filter_env(var.as_str());
+        }
+        if let Some(format) = config.format {
+            self = self.terminal_format(format);
+        }
+        if let Some(stream) = config.stream {
+            self = self.terminal_stream(stream);
+        }
+        let flags = [
+            (config.ansi, Self::ansi as fn(Self, bool) -> Self),
+            (config.display_filename, Self::display_filename),
+            (config.display_time, Self::display_time),
+            (config.display_level, Self::display_level),
+            (config.display_target, Self::display_target),
+            (config.display_thread_ids, Self::display_thread_ids),
+            (config.display_thread_names, Self::display_thread_names),
+            (config.display_line_number, Self::display_line_number),
+            (config.pretty, Self::pretty),
+            (config.uptime, Self::uptime),
+            (file.enabled, Self::log_to_file),
+            (file.ansi, Self::file_ansi),
+            (file.display_target, Self::file_display_target),
+            (file.display_thread_ids, Self::file_display_thread_ids),
+            (file.append, Self::append),
+            (file.non_blocking, Self::non_blocking),
+        ];
+        for (value, set) in flags {
+            if let Some(value) = value {
+                self = set(self, value);
+            }
+        }
+        if let Some(dir) = &file.dir {
+            self = self.log_dir(dir.clone());
+        }
+        if let Some(template) = &file.template {
+            self = self.logfile_template(template.as_str());
+        }
+        if let Some(level) = file.level {
+            self = self.file_level(level);
+        }
+        if let Some(format) = file.format {
+            self = self.file_format(format);
+        }
+        if let Some(rotation) = file.rotation {
+            self = self.rotation(rotation);
+        }
+        if let Some(compression) = file.compression {
+            self = self.compression(compression);
+        }
+        if let Some(max_files) = file.max_files {
+            self = self.max_logfiles(max_files);
+        }
+        if let Some(secs) = file.max_age_secs {
+            self = self.max_logfile_age(Duration::from_secs(secs));
+        }
+        self
+    }
+}
@@ -14,9 +14,22 @@ pub enum Error {
     /// Various errors raised when the messenger is used in a wrong way
     #[error("Bad usage")]
     Usage(String),
+    /// Filter directives could not be parsed
+    #[error("Bad filter directive")]
+    Filter(#[from] tracing_subscriber::filter::ParseError),
+    /// Filters could not be changed at runtime
+    #[error("Could not reload the filter")]
+    Reload(#[from] tracing_subscriber::reload::Error),
     /// Could not assign logger as the global default
     #[error("Could not assign logger as global default")]
     SetGlobalDefaultFail(#[from] SetGlobalDefaultError),
+    /// A [`LogFormat`](crate::LogFormat) was requested by a name that does not exist
+    #[error("Unknown log format: {0:?}")]
+    UnknownFormat(String),
+    /// Records of the `log` crate could not be captured
+    #[cfg(feature = "log")]
+    #[error("Could not capture the records of the log crate")]
+    LogBridge(#[from] tracing_log::log::SetLoggerError),
     /// any other error type, wrapped in [`anyhow::Error`]
     #[error(transparent)]
     Other(#[from] anyhow::Error),
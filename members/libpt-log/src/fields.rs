@@ -0,0 +1,65 @@
+//! # Span fields of each sink for [`pt-log`](crate)
+//!
+//! The formatting layers of [`tracing_subscriber`] store the formatted fields of a span in its
+//! extensions, keyed by the type of their field formatter. Layers with the same field formatter
+//! share these fields, so whichever sees a span first decides if they contain ANSI control
+//! sequences. To keep colors of the terminal out of the logfile, every sink wraps its field
+//! formatter in a [`SinkFields`] with its own marker type.
+
+use std::{fmt, marker::PhantomData};
+
+use tracing_subscriber::{
+    field::RecordFields,
+    fmt::{format::Writer, FormatFields},
+};
+
+/// Marks the span fields of the terminal sink
+#[derive(Debug)]
+pub struct Terminal;
+
+/// Marks the span fields of the logfile sink
+#[derive(Debug)]
+pub struct File;
+
+/// Marks the span fields of the recent events buffer
+#[derive(Debug)]
+pub struct Recent;
+
+/// Marks the span fields of the syslog sink
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct Syslog;
+
+/// Formats fields like `N`, but stores the fields of spans apart from those of other sinks
+pub struct SinkFields<K, N> {
+    /// the field formatter that does the work
+    inner: N,
+    /// the sink, only used for the type
+    sink: PhantomData<fn() -> K>,
+}
+
+impl<K, N> SinkFields<K, N> {
+    /// format fields with `inner`
+    pub const fn new(inner: N) -> Self {
+        Self {
+            inner,
+            sink: PhantomData,
+        }
+    }
+}
+
+impl<K, N: fmt::Debug> fmt::Debug for SinkFields<K, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SinkFields").field(&self.inner).finish()
+    }
+}
+
+impl<'writer, K, N> FormatFields<'writer> for SinkFields<K, N>
+where
+    K: 'static,
+    N: FormatFields<'writer> + 'static,
+{
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> fmt::Result {
+        self.inner.format_fields(writer, fields)
+    }
+}
@@ -0,0 +1,79 @@
+//! # Filters for [`pt-log`](crate)
+//!
+//! This module decides which events reach the sinks of a [Logger](crate::Logger), and lets the
+//! [Logger](crate::Logger) change that while the program is running.
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{
+    filter::{Directive, EnvFilter, ParseError},
+    reload, Registry,
+};
+
+/// A handle to change the filter of one sink at runtime
+pub type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// The filter of one sink, which can be changed with its [`FilterHandle`]
+pub type FilterLayer = reload::Layer<EnvFilter, Registry>;
+
+/// What a filter is made of: a default level refined by directives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterSpec {
+    /// the level for everything that no directive matches
+    pub level: LevelFilter,
+    /// directives like `mycrate=debug`, later ones take precedence
+    pub directives: Vec<Directive>,
+}
+
+impl FilterSpec {
+    /// parse a comma separated list of directives like `mycrate=debug,hyper=warn`
+    pub fn parse_directives(list: &str) -> Result<Vec<Directive>, ParseError> {
+        split_directives(list).map(str::parse).collect()
+    }
+
+    /// create the filter described by this spec
+    pub fn to_filter(&self) -> EnvFilter {
+        self.directives.iter().cloned().fold(
+            EnvFilter::default().add_directive(self.level.into()),
+            EnvFilter::add_directive,
+        )
+    }
+}
+
+/// The filter of one sink, and what it is currently made of
+#[derive(Debug)]
+pub struct SinkFilter {
+    /// changes the filter of the sink
+    handle: FilterHandle,
+    /// what the filter of the sink is made of
+    pub spec: FilterSpec,
+    /// the level of the sink is that of the [Logger](crate::Logger), not one of its own
+    pub inherits_level: bool,
+}
+
+impl SinkFilter {
+    /// create the filter layer for a sink, and a [`SinkFilter`] to change it later
+    ///
+    /// `inherits_level` tells if the level of `spec` is that of the [Logger](crate::Logger), and
+    /// should follow it when it changes.
+    pub fn new(spec: FilterSpec, inherits_level: bool) -> (FilterLayer, Self) {
+        let (layer, handle) = reload::Layer::new(spec.to_filter());
+        (
+            layer,
+            Self {
+                handle,
+                spec,
+                inherits_level,
+            },
+        )
+    }
+
+    /// apply the current [`Self::spec`] to the sink
+    pub fn reload(&self) -> Result<(), reload::Error> {
+        self.handle.reload(self.spec.to_filter())
+    }
+}
+
+/// split a comma separated list of directives
+pub fn split_directives(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|d| !d.is_empty())
+}
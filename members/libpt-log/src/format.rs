@@ -0,0 +1,94 @@
+//! # Output formats for [`pt-log`](crate)
+//!
+//! This module defines how the events of a [Logger](crate::Logger) are written to its sinks.
+
+use std::{fmt, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::error::Error;
+
+/// The format in which events are written
+///
+/// The format can be chosen separately for the terminal and the logfile, see
+/// [`LoggerBuilder::terminal_format`](crate::LoggerBuilder::terminal_format) and
+/// [`LoggerBuilder::file_format`](crate::LoggerBuilder::file_format).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// human readable lines of text
+    #[default]
+    Text,
+    /// one JSON object per line
+    ///
+    /// Each object contains the `timestamp`, `level`, `target`, the `message` and all other fields
+    /// of the event, as well as the stack of `spans` the event happened in, from the outermost
+    /// to the innermost one.
+    ///
+    /// ```json
+    /// {"timestamp":"2024-09-01T13:37:00.000000Z","level":"INFO","message":"hello","answer":42,"target":"app","spans":[{"name":"main"}]}
+    /// ```
+    Json,
+    /// an indented tree of the spans and the events in them
+    ///
+    /// Spans are drawn with box characters, with their fields on the line where they are first
+    /// entered and the elapsed time on the line where they are closed. This is meant for humans
+    /// following nested work in a single thread, events of concurrent threads end up in the same
+    /// tree.
+    ///
+    /// ```text
+    /// ┌─ download url="https://example.com"
+    /// │  INFO  connecting
+    /// │  ┌─ retry attempt=2
+    /// │  │  WARN  timed out
+    /// │  └─ retry 1.02s
+    /// └─ download 1.53s
+    /// ```
+    Tree,
+    /// one line of `key=value` pairs per event, as read by tools like Loki
+    ///
+    /// Each line has the timestamp as `ts`, the `level`, the `target`, the names of the spans
+    /// the event happened in as `span`, the fields of those spans from the outermost to the
+    /// innermost one, the message as `msg` and all other fields of the event. Values with spaces,
+    /// `=`, quotes, backslashes or control characters are quoted and escaped.
+    ///
+    /// ```text
+    /// ts=2024-09-01T13:37:00.000000Z level=info target=app span=main:download url=https://example.com msg="got it" bytes=42
+    /// ```
+    Logfmt,
+}
+
+impl LogFormat {
+    /// all formats, for example to list them in the help of a CLI
+    pub const ALL: &'static [Self] = &[Self::Text, Self::Json, Self::Tree, Self::Logfmt];
+
+    /// the name of the format, as used by [`FromStr`] and in config files
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+            Self::Tree => "tree",
+            Self::Logfmt => "logfmt",
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    /// parse the [name](Self::name) of a format, ignoring the case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|format| format.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::UnknownFormat(s.to_string()))
+    }
+}
@@ -11,28 +11,80 @@
 //! You can use this struct when use of the macros is not possible, but the macros should generally
 //! be preferred.
 //!
+//! Logfiles can be rolled over while the program is running, see [`Rotation`]. Events can be
+//! written as text or as JSON, see [`LogFormat`]. For tests, events can be recorded in memory,
+//! see [`capture`]. A [Logger] that is only active for one thread can be built with
+//! [`LoggerBuilder::build_scoped`]. The configuration can also be read from a config file, see
+//! [`config`]. With the `log` feature, which is enabled by default, records of the
+//! [`log`](tracing_log::log) crate can be logged as well, see [`LoggerBuilder::log_bridge`]. On
+//! Unix, events can also be sent to the local syslog daemon, see [`syslog`], and on all
+//! platforms to a log collector over the network, see [`network`]. With the `chrome` feature,
+//! spans can be exported for profiling, see [`LoggerBuilder::chrome_trace`].
+//!
 //! ## Technologies used for logging:
 //! - [`tracing`]: base logging crate
-//! - [`tracing_appender`]: Used to log to files
 //! - [`tracing_subscriber`]: Used to do actual logging, formatting, to stdout
 #![warn(clippy::pedantic, clippy::style, clippy::nursery)]
 
 use std::{
     fmt::{self, Debug},
+    ops::Deref,
     path::PathBuf,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
+    time::Duration,
 };
 
+#[cfg(feature = "log")]
+mod bridge;
+pub mod capture;
+#[cfg(feature = "chrome")]
+mod chrome;
+pub mod config;
 pub mod error;
 use error::Error;
+mod fields;
+use fields::SinkFields;
+mod filter;
+use filter::{FilterLayer, FilterSpec, SinkFilter};
+pub mod format;
+pub use format::LogFormat;
+pub mod logfile;
+mod logfmt;
+use logfmt::{Logfmt, LogfmtFields};
+pub mod network;
+pub use network::{Framing, Protocol};
+mod panic;
+mod recent;
+pub use logfile::{Compression, Rotation, DEFAULT_LOGFILE_TEMPLATE};
+use logfile::{LogfileHandle, LogfileTemplate, LogfileWriter, Retention};
+use network::NetworkHandle;
+use recent::RecentEvents;
+pub mod stream;
+pub use stream::TerminalStream;
+mod tree;
+use tree::TreeLayer;
+pub mod syslog;
+#[cfg(unix)]
+pub use syslog::{Facility, SyslogFormat};
 
 /// This is the magic dependency where the cool stuff happens
 ///
 /// I'm just repackaging it a little to make it more ergonomic
 pub use tracing;
-pub use tracing::{debug, error, info, trace, warn, Level};
+use tracing::subscriber::DefaultGuard;
+pub use tracing::{debug, error, info, level_filters::LevelFilter, trace, warn, Level};
 use tracing_subscriber::{
-    fmt::format::FmtSpan, layer::SubscriberExt as _, util::SubscriberInitExt, Layer,
+    filter::Directive,
+    fmt::{
+        format::{DefaultFields, FmtSpan, Format, Full, Pretty},
+        time::{FormatTime, SystemTime, Uptime},
+        MakeWriter,
+    },
+    layer::{Layered, SubscriberExt as _},
+    Layer, Registry,
 };
 
 use anyhow::{bail, Result};
@@ -45,6 +97,9 @@ pub const DEFAULT_LOG_DIR: &str = "/dev/null";
 
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+/// All layers of the subscriber built by a [`LoggerBuilder`]
+type Layers = Vec<Box<dyn Layer<Registry> + Send + Sync>>;
+
 /// Builder for a well configured [Logger]
 ///
 /// This struct helps configure a global logger that can be used with either macros or methods, see
@@ -70,21 +125,46 @@ pub struct LoggerBuilder {
     log_to_file: bool,
     /// logfiles would be created here
     log_dir: PathBuf,
-    /// use ANSI control sequences
+    /// how logfiles are named
+    logfile_template: String,
+    /// append to existing logfiles instead of truncating them
+    append: bool,
+    /// when to roll over to a new logfile
+    rotation: Rotation,
+    /// which old logfiles are kept
+    retention: Retention,
+    /// how finished logfiles are compressed
+    compression: Compression,
+    /// write to the logfile from a background thread
+    non_blocking: bool,
+    /// use ANSI control sequences on the terminal
     ansi: bool,
+    /// use ANSI control sequences in the logfile
+    file_ansi: bool,
     /// show which source file produces a log
     display_filename: bool,
     /// show the log level of the message
     display_level: bool,
     /// show target context
     display_target: bool,
+    /// show target context in the logfile, [`Self::display_target`] if [None]
+    file_display_target: Option<bool>,
     /// sets the maximum verbosity level.
     ///
     /// For example, if set to [Error](Level::ERROR), logs at [Info](Level::INFO) will not be
-    /// printed. If set to [Debug](Level::DEBUG), logs at [Info](Level::INFO) will be printed.
-    max_level: Level,
+    /// printed. If set to [Debug](Level::DEBUG), logs at [Info](Level::INFO) will be printed. If
+    /// set to [OFF](LevelFilter::OFF), nothing is printed.
+    max_level: LevelFilter,
+    /// the maximum verbosity level of the logfile, [`Self::max_level`] if [None]
+    file_level: Option<LevelFilter>,
+    /// filter directives like `mycrate=debug,hyper=warn`, refining [`Self::max_level`]
+    filter: String,
+    /// name of an environment variable with more filter directives
+    filter_env: Option<String>,
     /// show the id of the thread that created this message
     display_thread_ids: bool,
+    /// show the thread id in the logfile, [`Self::display_thread_ids`] if [None]
+    file_display_thread_ids: Option<bool>,
     /// show the name of the thread that created this message
     display_thread_names: bool,
     /// show which line in the source file produces a log
@@ -97,6 +177,44 @@ pub struct LoggerBuilder {
     uptime: bool,
     /// log when span things happen
     span_events: FmtSpan,
+    /// capture the records of the `log` crate
+    #[cfg(feature = "log")]
+    log_bridge: bool,
+    /// log panics
+    panic_hook: bool,
+    /// how many of the most recent events are kept in memory
+    recent_events: usize,
+    /// the lowest level of the events kept in memory
+    recent_level: Level,
+    /// format of the events written to the terminal
+    terminal_format: LogFormat,
+    /// the standard streams the terminal events are written to
+    terminal_stream: TerminalStream,
+    /// format of the events written to the logfile
+    file_format: LogFormat,
+    /// send events to the syslog daemon
+    #[cfg(unix)]
+    syslog: syslog::Syslog,
+    /// send events to a log collector
+    network: network::Network,
+    /// write spans to this file as Chrome trace events
+    #[cfg(feature = "chrome")]
+    chrome_trace: Option<PathBuf>,
+}
+
+/// The settings that can differ between the sinks of a [Logger]
+#[derive(Debug, Clone, Copy)]
+struct SinkSettings {
+    /// the maximum verbosity level
+    level: LevelFilter,
+    /// use ANSI control sequences
+    ansi: bool,
+    /// show target context
+    display_target: bool,
+    /// show the id of the thread that created this message
+    display_thread_ids: bool,
+    /// format of the events
+    format: LogFormat,
 }
 
 impl LoggerBuilder {
@@ -138,63 +256,473 @@ impl LoggerBuilder {
             warn!("trying to reinitialize the logger, ignoring");
             bail!(Error::Usage("logging is already initialized".to_string()));
         }
+        // another logger of the `log` crate must be noticed before anything is global
+        #[cfg(feature = "log")]
+        if self.log_bridge {
+            bridge::install().map_err(Error::from)?;
+        }
+        let (subscriber, logger, warnings) = self.subscriber()?;
+        tracing::subscriber::set_global_default(subscriber).map_err(Error::from)?;
+        // only now does the `log` crate know which records it can skip
+        #[cfg(feature = "log")]
+        bridge::update_max_level();
+        if self.panic_hook {
+            panic::install_hook(logger.logfile.clone(), logger.recent.clone());
+        }
+
+        INITIALIZED.store(true, Ordering::Relaxed);
+        report_warnings(&warnings);
+        Ok(logger)
+    }
+
+    /// use the configured settings to build a [Logger] that is only active for the current thread
+    ///
+    /// Unlike [`Self::build`], this does not install a global subscriber, so it can be called as
+    /// often as needed, for example once per test. The [Logger] is active until the returned
+    /// [`ScopedLogger`] is dropped, after which the previous subscriber of the thread, if any, is
+    /// active again. Events logged in other threads, even ones spawned by this thread, are not
+    /// handled by this [Logger]. Records of the `log` crate are not captured, even with
+    /// [`Self::log_bridge`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, Level, debug};
+    /// {
+    ///     let _logger = Logger::builder().set_level(Level::DEBUG).build_scoped().unwrap();
+    ///     debug!("handled by the scoped logger");
+    /// }
+    /// debug!("handled by the previous subscriber");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration is invalid or the logfile can not
+    /// be opened.
+    pub fn build_scoped(self) -> Result<ScopedLogger> {
+        let (subscriber, logger, warnings) = self.subscriber()?;
+        let guard = tracing::subscriber::set_default(subscriber);
+        report_warnings(&warnings);
+        Ok(ScopedLogger {
+            _guard: guard,
+            logger,
+        })
+    }
+
+    /// use the configured settings to build a [Logger] that is only active while `f` runs
+    ///
+    /// This is like [`Self::build_scoped`], but limited to a closure. The value returned by `f`
+    /// is passed through. Once `f` returns, the [Logger] is [flushed](Logger::flush) and dropped.
+    /// Like there, records of the `log` crate are not captured.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, info};
+    /// let answer = Logger::builder()
+    ///     .scope(|| {
+    ///         info!("computing");
+    ///         42
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(answer, 42);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration is invalid or the logfile can not
+    /// be opened.
+    pub fn scope<T>(self, f: impl FnOnce() -> T) -> Result<T> {
+        let (subscriber, _logger, warnings) = self.subscriber()?;
+        Ok(tracing::subscriber::with_default(subscriber, || {
+            report_warnings(&warnings);
+            f()
+        }))
+    }
+
+    /// Assembles the subscriber with all configured sinks, and the [Logger] to control it
+    ///
+    /// Also returns warnings about the configuration, see [`Self::warnings`].
+    fn subscriber(&self) -> Result<(Layered<Layers, Registry>, Logger, Vec<String>)> {
+        let (directives, ignored) = self.filter_directives()?;
+        let warnings = self.warnings(&ignored);
+        let mut filters = Vec::new();
+        let terminal = self.terminal_settings();
+        let (filter, sink_filter) = self.sink_filter(None, directives.clone());
+        filters.push(sink_filter);
+        let mut layers = vec![self
+            .fmt_layer::<fields::Terminal, _>(self.terminal_stream.make_writer(), terminal)
+            .with_filter(filter)
+            .boxed()];
+        let logfile = if self.log_to_file {
+            let writer = self
+                .logfile()?
+                .expect("logging to file is requested but logfile returned None");
+            let handle = if self.non_blocking {
+                LogfileHandle::non_blocking(writer)?
+            } else {
+                LogfileHandle::blocking(writer)
+            };
+            let file = self.file_settings();
+            let (filter, sink_filter) = self.sink_filter(self.file_level, directives.clone());
+            filters.push(sink_filter);
+            layers.push(
+                self.fmt_layer::<fields::File, _>(handle.clone(), file)
+                    .with_filter(filter)
+                    .boxed(),
+            );
+            Some(handle)
+        } else {
+            None
+        };
+        #[cfg(unix)]
+        if self.syslog.enabled {
+            let (filter, sink_filter) = self.sink_filter(self.syslog.level, directives.clone());
+            filters.push(sink_filter);
+            layers.push(self.syslog_layer()?.with_filter(filter).boxed());
+        }
+        #[cfg(feature = "chrome")]
+        let chrome = match &self.chrome_trace {
+            Some(path) => {
+                let (layer, guard) = chrome::layer(path).map_err(Error::from)?;
+                let (filter, sink_filter) = self.sink_filter(None, directives.clone());
+                filters.push(sink_filter);
+                layers.push(layer.with_filter(filter).boxed());
+                Some(Mutex::new(guard))
+            }
+            None => None,
+        };
+        let network = if self.network.enabled {
+            let handle = self.network.start(logfile.clone()).map_err(Error::from)?;
+            let (filter, sink_filter) = self.sink_filter(self.network.level, directives);
+            filters.push(sink_filter);
+            layers.push(
+                self.network_layer(handle.clone())
+                    .with_filter(filter)
+                    .boxed(),
+            );
+            Some(handle)
+        } else {
+            None
+        };
+        let recent = if self.recent_events > 0 {
+            let recent = RecentEvents::new(self.recent_events);
+            let sink = SinkSettings {
+                level: self.recent_level.into(),
+                ansi: false,
+                format: LogFormat::Text,
+                ..self.file_settings()
+            };
+            layers.push(
+                self.fmt_layer::<fields::Recent, _>(recent.clone(), sink)
+                    .with_filter(sink.level)
+                    .boxed(),
+            );
+            Some(recent)
+        } else {
+            None
+        };
+        let logger = Logger {
+            filters: Mutex::new(filters),
+            logfile,
+            network,
+            recent,
+            #[cfg(feature = "chrome")]
+            chrome,
+        };
+        Ok((
+            tracing_subscriber::registry().with(layers),
+            logger,
+            warnings,
+        ))
+    }
+
+    /// Collects what is wrong with the configuration, but not bad enough to fail the build
+    ///
+    /// These are logged once logging works, see [`report_warnings`]. `ignored` are the invalid
+    /// filter directives from the environment, see [`Self::filter_directives`].
+    fn warnings(&self, ignored: &[String]) -> Vec<String> {
+        let mut warnings: Vec<String> = ignored
+            .iter()
+            .map(|directive| {
+                format!("ignoring invalid filter directive from the environment: {directive:?}")
+            })
+            .collect();
+        if self.network.enabled && self.log_to_file && self.file_format != LogFormat::Json {
+            warnings.push(format!(
+                "events the network sink can not deliver are written to the logfile as json, \
+                 not as {}",
+                self.file_format.name()
+            ));
+        }
+        warnings
+    }
+
+    /// Creates the filter of a sink with its own `level`, or the level of the terminal if [None]
+    ///
+    /// Only in the latter case does the sink follow [`Logger::set_level`].
+    fn sink_filter(
+        &self,
+        level: Option<LevelFilter>,
+        directives: Vec<Directive>,
+    ) -> (FilterLayer, SinkFilter) {
+        SinkFilter::new(
+            FilterSpec {
+                level: level.unwrap_or(self.max_level),
+                directives,
+            },
+            level.is_none(),
+        )
+    }
+
+    /// Collects the filter directives refining the level of each sink
+    ///
+    /// It starts with the directives of [`Self::filter`] and then adds those from the environment
+    /// variable set with [`Self::filter_env`], so the environment can override the program.
+    ///
+    /// Invalid directives in the environment variable are not fatal, they are returned so that
+    /// they can be reported once logging works.
+    fn filter_directives(&self) -> Result<(Vec<Directive>, Vec<String>)> {
+        let mut directives = FilterSpec::parse_directives(&self.filter).map_err(Error::from)?;
+        let mut ignored = Vec::new();
+        if let Some(value) = self
+            .filter_env
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+        {
+            for directive in filter::split_directives(&value) {
+                match directive.parse() {
+                    Ok(directive) => directives.push(directive),
+                    Err(_) => ignored.push(directive.to_string()),
+                }
+            }
+        }
+        Ok((directives, ignored))
+    }
+
+    /// The settings of the terminal sink
+    const fn terminal_settings(&self) -> SinkSettings {
+        SinkSettings {
+            level: self.max_level,
+            ansi: self.ansi,
+            display_target: self.display_target,
+            display_thread_ids: self.display_thread_ids,
+            format: self.terminal_format,
+        }
+    }
+
+    /// The settings of the logfile sink, falling back to those of the terminal where the logfile
+    /// has no own setting
+    fn file_settings(&self) -> SinkSettings {
+        SinkSettings {
+            level: self.file_level.unwrap_or(self.max_level),
+            ansi: self.file_ansi,
+            display_target: self.file_display_target.unwrap_or(self.display_target),
+            display_thread_ids: self
+                .file_display_thread_ids
+                .unwrap_or(self.display_thread_ids),
+            format: self.file_format,
+        }
+    }
+
+    /// Creates a layer that writes the events to `writer` as configured by `sink`
+    ///
+    /// `K` marks the fields of spans formatted by this layer, see [`SinkFields`]. The layer is
+    /// not filtered yet.
+    fn fmt_layer<K, W>(
+        &self,
+        writer: W,
+        sink: SinkSettings,
+    ) -> Box<dyn Layer<Registry> + Send + Sync>
+    where
+        K: 'static,
+        W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    {
+        match sink.format {
+            LogFormat::Tree => return self.tree_layer::<K, _>(writer, sink),
+            LogFormat::Logfmt => return self.logfmt_layer::<K, _>(writer),
+            LogFormat::Text | LogFormat::Json => (),
+        }
         let layer = tracing_subscriber::fmt::layer()
-            .with_ansi(self.ansi)
-            .with_target(self.display_target)
+            .with_writer(writer)
+            .with_ansi(sink.ansi)
+            .with_target(sink.display_target)
+            .with_level(self.display_level)
             .with_file(self.display_filename)
-            .with_thread_ids(self.display_thread_ids)
+            .with_thread_ids(sink.display_thread_ids)
             .with_line_number(self.display_line_number)
             .with_thread_names(self.display_thread_names)
-            .with_span_events(self.span_events.clone())
-            .with_filter(tracing::level_filters::LevelFilter::from_level(
-                self.max_level,
-            ));
-        if self.log_to_file {
-            tracing_subscriber::registry()
-                .with(layer.and_then({
-                    tracing_subscriber::fmt::layer()
-                        .with_writer(
-                            self.logfile()?
-                                .expect("logging to file is requested but logfile returned None"),
-                        )
-                        .with_ansi(self.ansi)
-                        .with_target(self.display_target)
-                        .with_file(self.display_filename)
-                        .with_thread_ids(self.display_thread_ids)
-                        .with_line_number(self.display_line_number)
-                        .with_thread_names(self.display_thread_names)
-                        .with_span_events(self.span_events.clone())
-                        .with_filter(tracing::level_filters::LevelFilter::from_level(
-                            self.max_level,
-                        ))
-                }))
-                .init();
+            .with_span_events(self.span_events.clone());
+        if !self.show_time {
+            self.formatted_layer::<K, _, _>(layer.without_time(), sink.format)
+        } else if self.uptime {
+            self.formatted_layer::<K, _, _>(layer.with_timer(Uptime::default()), sink.format)
         } else {
-            tracing_subscriber::registry().with(layer).init();
+            self.formatted_layer::<K, _, _>(layer, sink.format)
         }
+    }
 
-        INITIALIZED.store(true, Ordering::Relaxed);
-        Ok(Logger {})
+    /// Creates a layer that writes the events to `writer` as a [tree](LogFormat::Tree)
+    ///
+    /// `K` marks the state the layer keeps for each span. The layer is not filtered yet.
+    fn tree_layer<K, W>(
+        &self,
+        writer: W,
+        sink: SinkSettings,
+    ) -> Box<dyn Layer<Registry> + Send + Sync>
+    where
+        K: 'static,
+        W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    {
+        let (ansi, level, target) = (sink.ansi, self.display_level, sink.display_target);
+        if !self.show_time {
+            TreeLayer::<K, _, _>::new(writer, (), ansi, level, target).boxed()
+        } else if self.uptime {
+            TreeLayer::<K, _, _>::new(writer, Uptime::default(), ansi, level, target).boxed()
+        } else {
+            TreeLayer::<K, _, _>::new(writer, SystemTime, ansi, level, target).boxed()
+        }
     }
 
-    /// Opens a new file for logging to.
+    /// Creates a layer that writes the events to `writer` as [logfmt](LogFormat::Logfmt)
     ///
-    /// Format: `{log_dir}/{consumer_name}_2024-09-01.log`
+    /// Like with JSON, the level and target are always included. `K` marks the fields of spans
+    /// formatted by this layer, see [`SinkFields`]. The layer is not filtered yet.
+    fn logfmt_layer<K, W>(&self, writer: W) -> Box<dyn Layer<Registry> + Send + Sync>
+    where
+        K: 'static,
+        W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    {
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+            .fmt_fields(SinkFields::<K, _>::new(LogfmtFields));
+        if !self.show_time {
+            layer.event_format(Logfmt::new(())).boxed()
+        } else if self.uptime {
+            layer.event_format(Logfmt::new(Uptime::default())).boxed()
+        } else {
+            layer.event_format(Logfmt::new(SystemTime)).boxed()
+        }
+    }
+
+    /// Finishes a layer from [`Self::fmt_layer`] once its timer is chosen
+    ///
+    /// The timer changes the type of the layer, so this is generic over it.
+    fn formatted_layer<K, W, T>(
+        &self,
+        layer: tracing_subscriber::fmt::Layer<Registry, DefaultFields, Format<Full, T>, W>,
+        format: LogFormat,
+    ) -> Box<dyn Layer<Registry> + Send + Sync>
+    where
+        K: 'static,
+        W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+        T: FormatTime + Send + Sync + 'static,
+    {
+        match format {
+            // pretty would otherwise always show the file and line number
+            LogFormat::Text if self.pretty => layer
+                .pretty()
+                .fmt_fields(SinkFields::<K, _>::new(Pretty::default()))
+                .with_file(self.display_filename)
+                .with_line_number(self.display_line_number)
+                .boxed(),
+            LogFormat::Text => layer
+                .fmt_fields(SinkFields::<K, _>::new(DefaultFields::new()))
+                .boxed(),
+            // machines don't need colors, but they always want to know the target
+            LogFormat::Json => layer
+                .json()
+                .with_ansi(false)
+                .with_target(true)
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(true)
+                .boxed(),
+            LogFormat::Tree | LogFormat::Logfmt => {
+                unreachable!("trees and logfmt have their own layers, see Self::fmt_layer")
+            }
+        }
+    }
+
+    /// Creates a layer that sends the events to the syslog daemon
+    ///
+    /// The syslog header already contains the time, level and process, so the events are
+    /// formatted without them. The layer is not filtered yet.
+    #[cfg(unix)]
+    fn syslog_layer(&self) -> Result<Box<dyn Layer<Registry> + Send + Sync>> {
+        let sink = self.syslog.connect().map_err(Error::from)?;
+        Ok(tracing_subscriber::fmt::layer()
+            .with_writer(sink)
+            .fmt_fields(SinkFields::<fields::Syslog, _>::new(DefaultFields::new()))
+            .with_ansi(false)
+            .without_time()
+            .with_level(false)
+            .with_target(self.display_target)
+            .with_file(self.display_filename)
+            .with_line_number(self.display_line_number)
+            .with_thread_ids(self.display_thread_ids)
+            .with_thread_names(self.display_thread_names)
+            .boxed())
+    }
+
+    /// Creates a layer that formats the events as JSON for the collector of the network sink
+    ///
+    /// The collector always gets the time, level and target. The layer is not filtered yet.
+    fn network_layer(&self, handle: NetworkHandle) -> Box<dyn Layer<Registry> + Send + Sync> {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(handle)
+            .with_ansi(false)
+            .with_level(true)
+            .with_target(true)
+            .with_file(self.display_filename)
+            .with_line_number(self.display_line_number)
+            .with_thread_ids(self.display_thread_ids)
+            .with_thread_names(self.display_thread_names)
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .boxed()
+    }
+
+    /// Opens a new file for logging to, which is rolled over according to [`Self::rotation`].
+    ///
+    /// Format: `{log_dir}/{logfile_template}`, by default `{log_dir}/{consumer_name}_2024-09-01.log`
     ///
     /// Will be none if [`Self::log_to_file`] is [false].
-    fn logfile(&self) -> Result<Option<std::fs::File>> {
+    fn logfile(&self) -> Result<Option<LogfileWriter>> {
         if !self.log_to_file {
             return Err(Error::LogfileButNoFilelog.into());
         }
-        let mut path = self.log_dir.clone();
-        std::fs::create_dir_all(&path)?;
-        path.push(format!(
-            "{}_{}.log",
+        let template = LogfileTemplate::parse(&self.logfile_template)?;
+        // the next logfile would have the same name and replace or continue the finished one
+        if matches!(self.rotation, Rotation::Daily | Rotation::Hourly)
+            && !template.changes_over_time()
+        {
+            return Err(Error::Usage(format!(
+                "a {:?} rotation needs {{date}} or {{time}} in the logfile template: {:?}",
+                self.rotation, self.logfile_template
+            ))
+            .into());
+        }
+        if self.compression != Compression::None && !self.rotation.finishes_logfiles(&template) {
+            return Err(Error::Usage(format!(
+                "compression needs a rotation that finishes logfiles, not {:?}",
+                self.rotation
+            ))
+            .into());
+        }
+        let writer = LogfileWriter::new(
+            self.log_dir.clone(),
             libpt_core::get_crate_name().unwrap_or_else(|| "logfile".to_string()),
-            chrono::Local::now().date_naive()
-        ));
-        let file = std::fs::File::create(path)?;
-        Ok(Some(file))
+            template,
+            self.rotation,
+            self.retention,
+            self.compression,
+            self.append,
+        )?;
+        Ok(Some(writer))
     }
 
     /// enable or disable logging to and creating of logfiles
@@ -219,12 +747,185 @@ impl LoggerBuilder {
         self
     }
 
-    /// enable or disable ANSI control sequences
+    /// set how logfiles in [`Self::log_dir`] are named
+    ///
+    /// The template may contain these placeholders:
+    ///
+    /// * `{crate}` – the name of the crate that logs, see [`libpt_core::get_crate_name`]
+    /// * `{date}` – the local date, including the hour if the [`Rotation`] is
+    ///   [hourly](Rotation::Hourly)
+    /// * `{time}` – the local time at which the logfile was opened
+    /// * `{pid}` – the id of the logging process
+    /// * `{hostname}` – the name of the machine
+    ///
+    /// Add `{pid}` or `{hostname}` if multiple instances of a program should log into the same
+    /// [`Self::log_dir`] without sharing their logfiles. Building the [Logger] fails if the
+    /// template contains an unknown placeholder, or if the [rotation](Self::rotation) is
+    /// [daily](Rotation::Daily) or [hourly](Rotation::Hourly) and the template contains neither
+    /// `{date}` nor `{time}`, as every new logfile would then replace the last one.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::Logger;
+    /// let builder = Logger::builder()
+    ///     .log_to_file(true)
+    ///     .log_dir("/tmp/logs".into())
+    ///     .logfile_template("{crate}_{date}_{hostname}_{pid}.log");
+    /// ```
+    ///
+    /// Default: [`DEFAULT_LOGFILE_TEMPLATE`] (`{crate}_{date}.log`)
+    #[must_use]
+    pub fn logfile_template(mut self, template: impl Into<String>) -> Self {
+        self.logfile_template = template.into();
+        self
+    }
+
+    /// append to existing logfiles instead of truncating them
+    ///
+    /// Keeping this enabled makes sure that restarting a program does not wipe the logs of an
+    /// earlier run with the same logfile name.
+    ///
+    /// Default: true
+    #[must_use]
+    pub const fn append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// write to the logfile from a background thread
+    ///
+    /// Writing to a file takes time, which is spent in every thread that logs something. With
+    /// this enabled, events are only handed to a background thread that writes them to the
+    /// logfile. Logging still blocks if that thread falls far behind, so no events are lost.
+    ///
+    /// Keep the [Logger] alive for as long as the program logs: dropping it, or calling
+    /// [`Logger::flush`], waits until everything logged so far was written.
+    ///
+    /// Default: false
+    #[must_use]
+    pub const fn non_blocking(mut self, non_blocking: bool) -> Self {
+        self.non_blocking = non_blocking;
+        self
+    }
+
+    /// set when to roll over to a new logfile
+    ///
+    /// Logfiles are rolled over while the program is running, so a long running program does not
+    /// keep writing into the same file forever. See [`Rotation`] for the available policies.
+    ///
+    /// Default: [`Rotation::Never`]
+    #[must_use]
+    pub const fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// compress logfiles once they are finished
+    ///
+    /// A logfile is finished when it is [rolled over](Self::rotation). It is then compressed in a
+    /// background thread, see [`Compression`]. [`Logger::flush`] waits until that is done.
+    /// Finished logfiles of earlier runs are compressed when the [Logger] is built. The
+    /// current logfile is never compressed, so building the [Logger] fails if the
+    /// [rotation](Self::rotation) never finishes a logfile.
+    ///
+    /// The algorithms need the `gzip` or `zstd` cargo features.
+    ///
+    /// Default: [`Compression::None`]
+    #[must_use]
+    pub const fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// keep at most `max_files` logfiles in [`Self::log_dir`], including the current one
+    ///
+    /// The oldest logfiles are deleted when the [Logger] is built and whenever the logfile is
+    /// [rolled over](Self::rotation). Only files whose names could have been made from the
+    /// [`Self::logfile_template`] for this crate are considered, including old logfiles of a
+    /// [size based rotation](Rotation::Size) and [compressed](Self::compression) ones. Other
+    /// files in the [`Self::log_dir`] are never touched.
+    ///
+    /// Default: [None], no logfiles are deleted
+    #[must_use]
+    pub const fn max_logfiles(mut self, max_files: usize) -> Self {
+        self.retention.max_files = Some(max_files);
+        self
+    }
+
+    /// delete logfiles in [`Self::log_dir`] that were last modified longer than `max_age` ago
+    ///
+    /// This happens at the same times and for the same files as with [`Self::max_logfiles`]. The
+    /// current logfile is never deleted.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::Logger;
+    /// # use std::time::Duration;
+    /// let builder = Logger::builder()
+    ///     .log_to_file(true)
+    ///     .log_dir("/tmp/logs".into())
+    ///     // a week
+    ///     .max_logfile_age(Duration::from_secs(7 * 24 * 60 * 60));
+    /// ```
+    ///
+    /// Default: [None], no logfiles are deleted
+    #[must_use]
+    pub const fn max_logfile_age(mut self, max_age: Duration) -> Self {
+        self.retention.max_age = Some(max_age);
+        self
+    }
+
+    /// set the [`LogFormat`] of the events written to the terminal
+    ///
+    /// Default: [`LogFormat::Text`]
+    #[must_use]
+    pub const fn terminal_format(mut self, format: LogFormat) -> Self {
+        self.terminal_format = format;
+        self
+    }
+
+    /// set the standard streams the events shown on the terminal are written to
+    ///
+    /// See [`TerminalStream`] for the options, for example to write warnings and errors to the
+    /// stderr, so that they do not end up in a pipe.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, Level, TerminalStream};
+    /// let builder = Logger::builder().terminal_stream(TerminalStream::Split {
+    ///     threshold: Level::WARN,
+    /// });
+    /// ```
+    ///
+    /// Default: [`TerminalStream::Stdout`]
+    #[must_use]
+    pub const fn terminal_stream(mut self, stream: TerminalStream) -> Self {
+        self.terminal_stream = stream;
+        self
+    }
+
+    /// set the [`LogFormat`] of the events written to the logfile
+    ///
+    /// This is independent of [`Self::terminal_format`], so a program can show text to humans
+    /// while writing JSON into the logfile for machines.
+    ///
+    /// Default: [`LogFormat::Text`]
+    #[must_use]
+    pub const fn file_format(mut self, format: LogFormat) -> Self {
+        self.file_format = format;
+        self
+    }
+
+    /// enable or disable ANSI control sequences on the terminal
     ///
     /// Disabling ANSI control sequences might improve compatibility and readability when the logs
     /// are displayed by a program that does not interpret them.
     ///
     /// Keeping ANSI control sequences enabled has the disadvantage of added colors for the logs.
+    /// The logfile is configured separately with [`Self::file_ansi`].
     ///
     /// Default: true
     #[must_use]
@@ -233,6 +934,18 @@ impl LoggerBuilder {
         self
     }
 
+    /// enable or disable ANSI control sequences in the logfile
+    ///
+    /// Most programs used to read logfiles show the control sequences as garbage, so this is
+    /// disabled by default.
+    ///
+    /// Default: false
+    #[must_use]
+    pub const fn file_ansi(mut self, file_ansi: bool) -> Self {
+        self.file_ansi = file_ansi;
+        self
+    }
+
     /// when making a log, display the source file in which a log was crated in
     ///
     /// Default: false
@@ -269,6 +982,15 @@ impl LoggerBuilder {
         self
     }
 
+    /// show target context in the logfile
+    ///
+    /// Default: [`Self::display_target`]
+    #[must_use]
+    pub const fn file_display_target(mut self, display_target: bool) -> Self {
+        self.file_display_target = Some(display_target);
+        self
+    }
+
     /// show the id of the thread that created this message
     ///
     /// Default: false
@@ -278,6 +1000,15 @@ impl LoggerBuilder {
         self
     }
 
+    /// show the id of the thread that created this message in the logfile
+    ///
+    /// Default: [`Self::display_thread_ids`]
+    #[must_use]
+    pub const fn file_display_thread_ids(mut self, display_thread_ids: bool) -> Self {
+        self.file_display_thread_ids = Some(display_thread_ids);
+        self
+    }
+
     /// show the name of the thread that created this message
     ///
     /// Default: false
@@ -316,10 +1047,93 @@ impl LoggerBuilder {
 
     /// set the lowest loglevel to be displayed
     ///
+    /// This is also the level of the logfile, unless it is set with [`Self::file_level`]. Besides
+    /// a [Level], this accepts a [`LevelFilter`], so that [`LevelFilter::OFF`] disables all output.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, Level, LevelFilter};
+    /// let verbose = Logger::builder().set_level(Level::DEBUG);
+    /// let silent = Logger::builder().set_level(LevelFilter::OFF);
+    /// ```
+    ///
     /// Default: [`Level::INFO`]
     #[must_use]
-    pub const fn set_level(mut self, max_level: Level) -> Self {
-        self.max_level = max_level;
+    pub fn set_level(mut self, max_level: impl Into<LevelFilter>) -> Self {
+        self.max_level = max_level.into();
+        self
+    }
+
+    /// set the lowest loglevel to be written to the logfile
+    ///
+    /// This way the logfile can record everything while the terminal only shows what a user
+    /// needs to see.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, Level};
+    /// let builder = Logger::builder()
+    ///     .set_level(Level::INFO)
+    ///     .log_to_file(true)
+    ///     .log_dir("/tmp/logs".into())
+    ///     .file_level(Level::TRACE);
+    /// ```
+    ///
+    /// Like with [`Self::set_level`], this can be a [Level] or a [`LevelFilter`].
+    ///
+    /// Default: [`Self::set_level`]
+    #[must_use]
+    pub fn file_level(mut self, file_level: impl Into<LevelFilter>) -> Self {
+        self.file_level = Some(file_level.into());
+        self
+    }
+
+    /// refine the level with filter directives, like `RUST_LOG` does
+    ///
+    /// Directives are separated by commas and have the form `target=level`, for example
+    /// `mycrate=debug,hyper=warn`. A directive without a target sets the default level, which
+    /// otherwise is [`Self::set_level`]. See
+    /// [`EnvFilter`](tracing_subscriber::filter::EnvFilter) for the full syntax, including filters
+    /// for spans and fields.
+    ///
+    /// Building the [Logger] fails if the directives are invalid.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, Level};
+    /// let builder = Logger::builder()
+    ///     .set_level(Level::DEBUG)
+    ///     .filter("hyper=warn,rustls=warn");
+    /// ```
+    ///
+    /// Default: no directives
+    #[must_use]
+    pub fn filter(mut self, directives: impl Into<String>) -> Self {
+        self.filter = directives.into();
+        self
+    }
+
+    /// read more filter directives from the environment variable `var`
+    ///
+    /// The directives have the same form as those of [`Self::filter`] and take precedence over
+    /// them. If the variable is not set, nothing changes. Invalid directives from the environment
+    /// are ignored with a warning instead of failing the build.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::Logger;
+    /// // MYAPP_LOG=mycrate=trace ./myapp
+    /// let builder = Logger::builder().filter_env("MYAPP_LOG");
+    /// ```
+    ///
+    /// Default: [None], the environment is not read
+    #[must_use]
+    pub fn filter_env(mut self, var: impl Into<String>) -> Self {
+        self.filter_env = Some(var.into());
         self
     }
 
@@ -331,6 +1145,297 @@ impl LoggerBuilder {
         self.span_events = span_events;
         self
     }
+
+    /// capture the records of the [`log`](tracing_log::log) crate
+    ///
+    /// Many crates log through the `log` facade instead of [`tracing`]. With this enabled, their
+    /// records are logged like any other event: their level and target are kept, and they pass
+    /// the same filters, including changes made with [`Logger::set_level`] and
+    /// [`Logger::set_filter`].
+    ///
+    /// The `log` crate only has a global logger, so this only has an effect for [`Self::build`],
+    /// not for [`Self::build_scoped`] and [`Self::scope`]. Building the [Logger] fails if another
+    /// logger was already installed for the `log` crate.
+    ///
+    /// Default: false
+    #[cfg(feature = "log")]
+    #[must_use]
+    pub const fn log_bridge(mut self, log_bridge: bool) -> Self {
+        self.log_bridge = log_bridge;
+        self
+    }
+
+    /// log panics, so that they also end up in the logfile
+    ///
+    /// This installs a panic hook that logs each panic at [`Level::ERROR`], with the name of the
+    /// thread, the location in the source and a backtrace. Afterwards, the logfile is
+    /// [flushed](Logger::flush) and the panic is passed on to the previous hook, which usually
+    /// prints it to the stderr.
+    ///
+    /// The hook is only installed by [`Self::build`], since it applies to the whole program.
+    ///
+    /// If [recent events](Self::recent_events) are kept, they are dumped after the panic was
+    /// logged.
+    ///
+    /// Default: false
+    #[must_use]
+    pub const fn panic_hook(mut self, panic_hook: bool) -> Self {
+        self.panic_hook = panic_hook;
+        self
+    }
+
+    /// keep the most recent events in memory, to dump them when something fails
+    ///
+    /// Up to `capacity` events at the [`Self::recent_level`] or above are kept, regardless of the
+    /// levels and filters of the sinks. This way, a program can log at [`Level::INFO`] and still
+    /// see what happened in detail right before a failure. The events are written to the logfile,
+    /// or to the stderr if there is none, by [`Logger::dump_recent`] and, if the
+    /// [panic hook](Self::panic_hook) is installed, when the program panics. They are always
+    /// formatted as [text](LogFormat::Text), whatever the
+    /// [format of the logfile](Self::file_format).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, Level};
+    /// let builder = Logger::builder()
+    ///     .set_level(Level::INFO)
+    ///     .recent_events(200)
+    ///     .recent_level(Level::DEBUG)
+    ///     .panic_hook(true);
+    /// ```
+    ///
+    /// Default: 0, no events are kept
+    #[must_use]
+    pub const fn recent_events(mut self, capacity: usize) -> Self {
+        self.recent_events = capacity;
+        self
+    }
+
+    /// set the lowest loglevel of the [recent events](Self::recent_events) kept in memory
+    ///
+    /// Default: [`Level::TRACE`]
+    #[must_use]
+    pub const fn recent_level(mut self, recent_level: Level) -> Self {
+        self.recent_level = recent_level;
+        self
+    }
+
+    /// enable or disable sending events to the local syslog daemon
+    ///
+    /// Each event is sent as one datagram to the Unix socket at [`Self::syslog_path`], with its
+    /// [Level] mapped to a syslog severity, see [`syslog::severity`]. Building the [Logger] fails
+    /// if nothing listens at that socket.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// # use libpt_log::{Logger, Facility, SyslogFormat};
+    /// let logger = Logger::builder()
+    ///     .log_to_syslog(true)
+    ///     .syslog_format(SyslogFormat::Rfc3164)
+    ///     .syslog_facility(Facility::Daemon)
+    ///     .syslog_app_name("mydaemon")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// Default: false
+    #[cfg(unix)]
+    #[must_use]
+    pub const fn log_to_syslog(mut self, log_to_syslog: bool) -> Self {
+        self.syslog.enabled = log_to_syslog;
+        self
+    }
+
+    /// set the Unix datagram socket of the syslog daemon
+    ///
+    /// Default: [`syslog::DEFAULT_SYSLOG_PATH`] (`/dev/log`)
+    #[cfg(unix)]
+    #[must_use]
+    pub fn syslog_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.syslog.path = path.into();
+        self
+    }
+
+    /// set the [`SyslogFormat`] of the messages sent to the syslog daemon
+    ///
+    /// Default: [`SyslogFormat::Rfc5424`]
+    #[cfg(unix)]
+    #[must_use]
+    pub const fn syslog_format(mut self, format: SyslogFormat) -> Self {
+        self.syslog.format = format;
+        self
+    }
+
+    /// set the [`Facility`] of the messages sent to the syslog daemon
+    ///
+    /// Default: [`Facility::User`]
+    #[cfg(unix)]
+    #[must_use]
+    pub const fn syslog_facility(mut self, facility: Facility) -> Self {
+        self.syslog.facility = facility;
+        self
+    }
+
+    /// set the name of the program in the messages sent to the syslog daemon
+    ///
+    /// Default: the name of the crate, see [`libpt_core::get_crate_name`]
+    #[cfg(unix)]
+    #[must_use]
+    pub fn syslog_app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.syslog.app_name = Some(app_name.into());
+        self
+    }
+
+    /// set the lowest loglevel to be sent to the syslog daemon
+    ///
+    /// Like with [`Self::file_level`], this can differ from the level of the terminal.
+    ///
+    /// Default: [`Self::set_level`]
+    #[cfg(unix)]
+    #[must_use]
+    pub fn syslog_level(mut self, syslog_level: impl Into<LevelFilter>) -> Self {
+        self.syslog.level = Some(syslog_level.into());
+        self
+    }
+
+    /// enable or disable sending events to a log collector over the network
+    ///
+    /// The events are sent as JSON or GELF, see [`Self::network_framing`], over TCP or UDP, see
+    /// [`Self::network_protocol`], from a background thread, so logging never waits for the
+    /// collector. While the collector is unreachable, events are buffered, see
+    /// [`Self::network_buffer`], and the connection is retried. If it stays unreachable for
+    /// longer than [`Self::network_fallback_after`], or the buffer runs full, the events are
+    /// written to the logfile as JSON lines instead. Without a [logfile](Self::log_to_file), they
+    /// are dropped.
+    ///
+    /// Events the collector missed end up in the logfile in addition to those of the logfile
+    /// itself, so give the logfile a lower [level](Self::file_level) than the network to avoid
+    /// duplicates. They are written as JSON, so a warning is logged if the
+    /// [format of the logfile](Self::file_format) is another one.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// # use libpt_log::{Logger, Framing, Protocol};
+    /// let logger = Logger::builder()
+    ///     .log_to_network(true)
+    ///     .network_address("logs.example.com:12201")
+    ///     .network_protocol(Protocol::Udp)
+    ///     .network_framing(Framing::Gelf)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// Default: false
+    #[must_use]
+    pub const fn log_to_network(mut self, log_to_network: bool) -> Self {
+        self.network.enabled = log_to_network;
+        self
+    }
+
+    /// set the address of the log collector, as `host:port`
+    ///
+    /// The host is resolved whenever the connection is (re)established.
+    ///
+    /// Default: [`network::DEFAULT_NETWORK_ADDRESS`] (`localhost:12201`)
+    #[must_use]
+    pub fn network_address(mut self, address: impl Into<String>) -> Self {
+        self.network.address = address.into();
+        self
+    }
+
+    /// set the [`Protocol`] used to reach the log collector
+    ///
+    /// Default: [`Protocol::Tcp`]
+    #[must_use]
+    pub const fn network_protocol(mut self, protocol: Protocol) -> Self {
+        self.network.protocol = protocol;
+        self
+    }
+
+    /// set the [`Framing`] of the events sent to the log collector
+    ///
+    /// Default: [`Framing::Json`]
+    #[must_use]
+    pub const fn network_framing(mut self, framing: Framing) -> Self {
+        self.network.framing = framing;
+        self
+    }
+
+    /// set the lowest loglevel to be sent to the log collector
+    ///
+    /// Like with [`Self::file_level`], this can differ from the level of the terminal.
+    ///
+    /// Default: [`Self::set_level`]
+    #[must_use]
+    pub fn network_level(mut self, network_level: impl Into<LevelFilter>) -> Self {
+        self.network.level = Some(network_level.into());
+        self
+    }
+
+    /// set how many events are buffered while the log collector is unreachable
+    ///
+    /// When the buffer is full, the oldest events are written to the logfile.
+    ///
+    /// Default: [`network::DEFAULT_NETWORK_BUFFER`] (1024)
+    #[must_use]
+    pub const fn network_buffer(mut self, events: usize) -> Self {
+        self.network.buffer = events;
+        self
+    }
+
+    /// set how long the log collector may be unreachable before the buffered events are written
+    /// to the logfile
+    ///
+    /// Default: [`network::DEFAULT_NETWORK_FALLBACK`] (30 seconds)
+    #[must_use]
+    pub const fn network_fallback_after(mut self, fallback_after: Duration) -> Self {
+        self.network.fallback_after = fallback_after;
+        self
+    }
+
+    /// write the spans to the file at `path` as Chrome trace events, for profiling
+    ///
+    /// The file can be opened in [Perfetto](https://ui.perfetto.dev) or `chrome://tracing`, which
+    /// show when each span was entered and exited, with one timeline per thread. Events are shown
+    /// as instants on those timelines, and the fields of spans and events as their arguments.
+    /// Only spans and events passing the [level](Self::set_level) and [filter](Self::filter) of
+    /// the terminal are recorded.
+    ///
+    /// The file is written from a background thread. It is only complete once the [Logger] is
+    /// dropped, [`Logger::flush`] just writes out what was recorded so far. An existing file at
+    /// `path` is overwritten.
+    ///
+    /// This needs the `chrome` cargo feature.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// # use libpt_log::{Logger, info, tracing::info_span};
+    /// let logger = Logger::builder()
+    ///     .chrome_trace("trace.json")
+    ///     .build()
+    ///     .unwrap();
+    /// info_span!("work").in_scope(|| info!("working"));
+    /// drop(logger);
+    /// ```
+    ///
+    /// Default: [None], no trace is written
+    #[cfg(feature = "chrome")]
+    #[must_use]
+    pub fn chrome_trace(mut self, path: impl Into<PathBuf>) -> Self {
+        self.chrome_trace = Some(path.into());
+        self
+    }
+}
+
+/// log the warnings about the configuration of a [Logger], see [`LoggerBuilder::warnings`]
+fn report_warnings(warnings: &[String]) {
+    for warning in warnings {
+        warn!("{warning}");
+    }
 }
 
 impl Default for LoggerBuilder {
@@ -338,18 +1443,43 @@ impl Default for LoggerBuilder {
         Self {
             log_to_file: false,
             log_dir: PathBuf::from(DEFAULT_LOG_DIR),
+            logfile_template: DEFAULT_LOGFILE_TEMPLATE.to_string(),
+            append: true,
+            rotation: Rotation::Never,
+            retention: Retention::default(),
+            compression: Compression::None,
+            non_blocking: false,
             ansi: true,
+            file_ansi: false,
             display_filename: false,
             display_level: true,
             display_target: false,
-            max_level: DEFAULT_LOG_LEVEL,
+            file_display_target: None,
+            max_level: LevelFilter::from_level(DEFAULT_LOG_LEVEL),
+            file_level: None,
+            filter: String::new(),
+            filter_env: None,
             display_thread_ids: false,
+            file_display_thread_ids: None,
             display_thread_names: false,
             display_line_number: false,
             pretty: false,
             show_time: true,
             uptime: false,
             span_events: FmtSpan::NONE,
+            #[cfg(feature = "log")]
+            log_bridge: false,
+            panic_hook: false,
+            recent_events: 0,
+            recent_level: Level::TRACE,
+            terminal_format: LogFormat::Text,
+            terminal_stream: TerminalStream::Stdout,
+            file_format: LogFormat::Text,
+            #[cfg(unix)]
+            syslog: syslog::Syslog::default(),
+            network: network::Network::default(),
+            #[cfg(feature = "chrome")]
+            chrome_trace: None,
         }
     }
 }
@@ -398,7 +1528,19 @@ impl Default for LoggerBuilder {
 /// # }
 ///
 /// ```
-pub struct Logger;
+pub struct Logger {
+    /// the filters of all sinks, to change them at runtime
+    filters: Mutex<Vec<SinkFilter>>,
+    /// the logfile, if logging to a file was requested
+    logfile: Option<LogfileHandle>,
+    /// the network sink, if sending events to a collector was requested
+    network: Option<NetworkHandle>,
+    /// finishes the Chrome trace when dropped, if exporting one was requested
+    #[cfg(feature = "chrome")]
+    chrome: Option<Mutex<tracing_chrome::FlushGuard>>,
+    /// the most recent events, if keeping them was requested
+    recent: Option<RecentEvents>,
+}
 
 /// ## Main implementation
 impl Logger {
@@ -408,6 +1550,114 @@ impl Logger {
         LoggerBuilder::default()
     }
 
+    /// change the lowest loglevel to be displayed while the program is running
+    ///
+    /// The change applies to the terminal and to all sinks that use its level. Sinks that were
+    /// given their own level, like the logfile with [`LoggerBuilder::file_level`], keep it. Filter
+    /// directives set with
+    /// [`LoggerBuilder::filter`] or [`Self::set_filter`] still take precedence for the targets
+    /// they match. Like with [`LoggerBuilder::set_level`], this can be a [Level] or a
+    /// [`LevelFilter`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use libpt_log::{Logger, Level, debug};
+    /// let logger = Logger::builder().build().unwrap();
+    /// debug!("not shown");
+    /// logger.set_level(Level::DEBUG).unwrap();
+    /// debug!("shown");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if the subscriber of the [Logger] no longer exists.
+    #[allow(clippy::significant_drop_tightening)] // keep concurrent changes in order
+    pub fn set_level(&self, level: impl Into<LevelFilter>) -> Result<()> {
+        let level = level.into();
+        let mut filters = self.filters.lock().unwrap_or_else(PoisonError::into_inner);
+        for filter in filters.iter_mut().filter(|filter| filter.inherits_level) {
+            filter.spec.level = level;
+        }
+        Self::reload_filters(&filters)
+    }
+
+    /// replace the filter directives while the program is running
+    ///
+    /// The directives have the same form as those of [`LoggerBuilder::filter`] and replace all
+    /// directives given to the [`LoggerBuilder`], including those from the environment. The levels
+    /// of the sinks stay the same. The change applies to all sinks.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the directives are invalid, in which case the filters are left unchanged, or if
+    /// the subscriber of the [Logger] no longer exists.
+    #[allow(clippy::significant_drop_tightening)] // keep concurrent changes in order
+    pub fn set_filter(&self, directives: &str) -> Result<()> {
+        let directives = FilterSpec::parse_directives(directives).map_err(Error::from)?;
+        let mut filters = self.filters.lock().unwrap_or_else(PoisonError::into_inner);
+        for filter in filters.iter_mut() {
+            filter.spec.directives.clone_from(&directives);
+        }
+        Self::reload_filters(&filters)
+    }
+
+    /// make sure everything logged so far is written to the logfile
+    ///
+    /// If the logfile is written [from a background thread](LoggerBuilder::non_blocking), this
+    /// waits until that thread has caught up. If a finished logfile is being
+    /// [compressed](LoggerBuilder::compression), this waits for that as well. The
+    /// [network sink](LoggerBuilder::log_to_network) tries once more to deliver what it
+    /// buffered. A [Chrome trace](LoggerBuilder::chrome_trace) is written out up to now. This
+    /// also happens when the [Logger] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the logfile could not be written.
+    pub fn flush(&self) -> Result<()> {
+        // undeliverable events of the network sink go to the logfile, so this comes first
+        if let Some(network) = &self.network {
+            network.flush().map_err(Error::from)?;
+        }
+        if let Some(logfile) = &self.logfile {
+            logfile.flush().map_err(Error::from)?;
+        }
+        #[cfg(feature = "chrome")]
+        if let Some(chrome) = &self.chrome {
+            chrome
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .flush();
+        }
+        Ok(())
+    }
+
+    /// write the [recent events](LoggerBuilder::recent_events) and forget them
+    ///
+    /// The events are written to the logfile, or to the stderr if there is no logfile. Call this
+    /// when something failed, to get the detailed context that the sinks did not show. Nothing
+    /// happens if no recent events are kept.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the events could not be written.
+    pub fn dump_recent(&self) -> Result<()> {
+        if let Some(recent) = &self.recent {
+            recent.dump(self.logfile.as_ref()).map_err(Error::from)?;
+        }
+        Ok(())
+    }
+
+    /// apply the current specs to the filters of all sinks
+    fn reload_filters(filters: &[SinkFilter]) -> Result<()> {
+        for filter in filters {
+            filter.reload().map_err(Error::from)?;
+        }
+        #[cfg(feature = "log")]
+        bridge::update_max_level();
+        Ok(())
+    }
+
     /// ## logging at [`Level::ERROR`]
     pub fn error<T>(&self, printable: T)
     where
@@ -456,6 +1706,33 @@ impl fmt::Debug for Logger {
     }
 }
 
+/// A [Logger] that is only active for the current thread
+///
+/// Create it with [`LoggerBuilder::build_scoped`]. When it is dropped, the previous subscriber of
+/// the thread is restored. It dereferences to the [Logger], so its methods can be used as usual.
+#[derive(Debug)]
+pub struct ScopedLogger {
+    /// restores the previous subscriber of the thread when dropped
+    _guard: DefaultGuard,
+    /// controls the scoped subscriber
+    logger: Logger,
+}
+
+impl Deref for ScopedLogger {
+    type Target = Logger;
+
+    fn deref(&self) -> &Self::Target {
+        &self.logger
+    }
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        // there is nowhere left to report this error to
+        let _ = self.flush();
+    }
+}
+
 impl Default for Logger {
     fn default() -> Self {
         LoggerBuilder::default()
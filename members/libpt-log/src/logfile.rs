@@ -0,0 +1,800 @@
+//! # Logfile handling for [`pt-log`](crate)
+//!
+//! This module opens the logfiles a [Logger](crate::Logger) writes to and rolls them over
+//! according to a [`Rotation`] policy while the program is running.
+//!
+//! The names of the logfiles are created from a template, see
+//! [`LoggerBuilder::logfile_template`](crate::LoggerBuilder::logfile_template).
+//!
+//! Finished logfiles can be compressed, see [`Compression`]. Old logfiles can be deleted
+//! automatically, see
+//! [`LoggerBuilder::max_logfiles`](crate::LoggerBuilder::max_logfiles) and
+//! [`LoggerBuilder::max_logfile_age`](crate::LoggerBuilder::max_logfile_age).
+//!
+//! Writing to the logfile can be moved to a background thread, see
+//! [`LoggerBuilder::non_blocking`](crate::LoggerBuilder::non_blocking).
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local, TimeDelta, Timelike};
+use serde::Deserialize;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::error::Error;
+
+/// The template used for the names of logfiles when none is specified
+pub const DEFAULT_LOGFILE_TEMPLATE: &str = "{crate}_{date}.log";
+
+/// How many events may wait for the background thread of a non-blocking logfile
+///
+/// If the background thread falls this far behind, logging blocks until it catches up, so no
+/// events are lost.
+const NON_BLOCKING_CAPACITY: usize = 128_000;
+
+/// When to roll over to a new logfile
+///
+/// Set this with [`LoggerBuilder::rotation`](crate::LoggerBuilder::rotation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rotation {
+    /// keep writing into the logfile that was opened when the [Logger](crate::Logger) was built
+    #[default]
+    Never,
+    /// start a new logfile at midnight (local time)
+    ///
+    /// The `{date}` in the [logfile template](crate::LoggerBuilder::logfile_template) looks like
+    /// `2024-09-01`.
+    Daily,
+    /// start a new logfile at the start of every hour (local time)
+    ///
+    /// The `{date}` in the [logfile template](crate::LoggerBuilder::logfile_template) looks like
+    /// `2024-09-01_13`.
+    Hourly,
+    /// start a new logfile once the current one would grow larger than `max_bytes`
+    ///
+    /// The full logfile is renamed to `{name}.log.1`, an older `{name}.log.1` becomes
+    /// `{name}.log.2` and so on. At most `keep` of these old logfiles are kept, the oldest ones
+    /// are deleted.
+    Size {
+        /// maximum size of a single logfile in bytes
+        max_bytes: u64,
+        /// how many old logfiles are kept next to the current one
+        keep: usize,
+    },
+}
+
+impl Rotation {
+    /// check if logfiles named after `template` are ever finished, so that they can be
+    /// [compressed](Compression)
+    ///
+    /// A [size based](Self::Size) rotation that keeps no old logfiles truncates the logfile
+    /// instead of finishing it, and a time based one keeps writing into the same logfile if its
+    /// name does not change over time.
+    pub(crate) fn finishes_logfiles(self, template: &LogfileTemplate) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Size { keep, .. } => keep > 0,
+            Self::Daily | Self::Hourly => template.changes_over_time(),
+        }
+    }
+
+    /// the part of the filename that identifies the period a logfile belongs to
+    fn period(self, now: DateTime<Local>) -> String {
+        match self {
+            Self::Hourly => now.format("%Y-%m-%d_%H").to_string(),
+            _ => now.date_naive().to_string(),
+        }
+    }
+
+    /// the point in time at which the logfile opened at `now` has to be rolled over
+    ///
+    /// Will be [None] if this [Rotation] does not depend on time.
+    fn next_rollover(self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = match self {
+            Self::Daily => now.date_naive().and_hms_opt(0, 0, 0)? + TimeDelta::days(1),
+            Self::Hourly => now.date_naive().and_hms_opt(now.hour(), 0, 0)? + TimeDelta::hours(1),
+            Self::Never | Self::Size { .. } => return None,
+        };
+        // the start of the next period might not exist in local time because of DST, in that
+        // case we just try again a little later
+        start
+            .and_local_timezone(Local)
+            .earliest()
+            .or_else(|| Some(now + TimeDelta::hours(1)))
+    }
+}
+
+/// Which old logfiles are kept in the log directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub(crate) struct Retention {
+    /// keep at most this many logfiles, including the current one
+    pub(crate) max_files: Option<usize>,
+    /// delete logfiles that were last modified longer ago than this
+    pub(crate) max_age: Option<Duration>,
+}
+
+impl Retention {
+    /// check if no logfiles are ever deleted
+    const fn keeps_everything(self) -> bool {
+        self.max_files.is_none() && self.max_age.is_none()
+    }
+}
+
+/// The extensions of compressed logfiles, see [`Compression`]
+const COMPRESSED_EXTENSIONS: [&str; 2] = [".gz", ".zst"];
+
+/// How finished logfiles are compressed
+///
+/// A logfile is finished once the [Logger](crate::Logger) rolls over to a new one, see
+/// [`Rotation`]. It is then compressed in a background thread, and the uncompressed logfile is
+/// deleted once that is done. The compressed logfile keeps the name of the logfile, with the
+/// extension of the compression added, like `{name}.log.gz`. Finished logfiles that an earlier
+/// run left uncompressed are compressed when the [Logger](crate::Logger) is built: the old
+/// logfiles of a [size based](Rotation::Size) rotation, and the logfiles of a time based one
+/// that were last written in an earlier period.
+///
+/// The logfile that is currently written is never compressed, so compression needs a [Rotation]
+/// that finishes logfiles: building a [Logger](crate::Logger) with [`Rotation::Never`] or a
+/// [`Rotation::Size`] that keeps no old logfiles fails.
+///
+/// Set this with [`LoggerBuilder::compression`](crate::LoggerBuilder::compression). The
+/// compression algorithms are only available with the cargo features of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    /// keep finished logfiles as they are
+    #[default]
+    None,
+    /// compress finished logfiles with gzip, adding `.gz` to their names
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// compress finished logfiles with zstd, adding `.zst` to their names
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// compress the logfile at `path` next to it, then delete it
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    fn compress(self, path: &Path) -> io::Result<()> {
+        match self {
+            Self::None => return Ok(()),
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                let (mut input, output) = Self::open_pair(path, ".gz")?;
+                let mut encoder =
+                    flate2::write::GzEncoder::new(output, flate2::Compression::default());
+                io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                let (mut input, output) = Self::open_pair(path, ".zst")?;
+                let mut encoder = zstd::Encoder::new(output, 0)?;
+                io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+        }
+        fs::remove_file(path)
+    }
+
+    /// without any compression algorithms, there is nothing to do
+    #[cfg(not(any(feature = "gzip", feature = "zstd")))]
+    #[allow(clippy::unnecessary_wraps, clippy::unused_self)] // same as with compression algorithms
+    const fn compress(self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// open the logfile at `path` and create the compressed file with the added `extension`
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    fn open_pair(path: &Path, extension: &str) -> io::Result<(File, BufWriter<File>)> {
+        let mut target = path.as_os_str().to_owned();
+        target.push(extension);
+        Ok((File::open(path)?, BufWriter::new(File::create(target)?)))
+    }
+}
+
+/// One part of a [`LogfileTemplate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// text that is put into the filename as is
+    Literal(String),
+    /// `{crate}`: the name of the crate that logs, see [`libpt_core::get_crate_name`]
+    Crate,
+    /// `{date}`: the date of the current [Rotation] period
+    Date,
+    /// `{time}`: the local time at which the logfile was opened
+    Time,
+    /// `{pid}`: the id of the logging process
+    Pid,
+    /// `{hostname}`: the name of the machine
+    Hostname,
+}
+
+/// A parsed filename template for logfiles
+///
+/// The available placeholders are `{crate}`, `{date}`, `{time}`, `{pid}` and `{hostname}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LogfileTemplate {
+    segments: Vec<Segment>,
+}
+
+impl LogfileTemplate {
+    /// parse a template like `{crate}_{date}.log`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the template contains an unknown or unterminated placeholder, or if it would
+    /// produce a path instead of a filename.
+    pub(crate) fn parse(template: &str) -> Result<Self, Error> {
+        if template.is_empty() || template.contains(std::path::is_separator) {
+            return Err(Error::Usage(format!(
+                "logfile template must be a filename: {template:?}"
+            )));
+        }
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            let Some(len) = rest[start..].find('}') else {
+                return Err(Error::Usage(format!(
+                    "unterminated placeholder in logfile template: {template:?}"
+                )));
+            };
+            segments.push(match &rest[start + 1..start + len] {
+                "crate" => Segment::Crate,
+                "date" => Segment::Date,
+                "time" => Segment::Time,
+                "pid" => Segment::Pid,
+                "hostname" => Segment::Hostname,
+                other => {
+                    return Err(Error::Usage(format!(
+                        "unknown placeholder {{{other}}} in logfile template: {template:?}"
+                    )))
+                }
+            });
+            rest = &rest[start + len + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+        Ok(Self { segments })
+    }
+
+    /// check if the filename changes over time, which a time based [Rotation] needs to roll over
+    /// to a new logfile
+    pub(crate) fn changes_over_time(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Date | Segment::Time))
+    }
+
+    /// fill in the placeholders for a logfile of the crate `name` opened at `now`
+    fn render(&self, name: &str, rotation: Rotation, now: DateTime<Local>) -> String {
+        let mut filename = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => filename.push_str(text),
+                Segment::Crate => filename.push_str(name),
+                Segment::Date => filename.push_str(&rotation.period(now)),
+                Segment::Time => filename.push_str(&now.format("%H-%M-%S").to_string()),
+                Segment::Pid => filename.push_str(&std::process::id().to_string()),
+                Segment::Hostname => {
+                    filename.push_str(&gethostname::gethostname().to_string_lossy());
+                }
+            }
+        }
+        filename
+    }
+
+    /// check if `filename` could have been rendered from this template for the crate `name`
+    ///
+    /// The placeholders that change between logfiles, `{date}`, `{time}` and `{pid}`, match any
+    /// value that looks like theirs.
+    fn matches(&self, filename: &str, name: &str, hostname: &str) -> bool {
+        Self::matches_segments(&self.segments, filename, name, hostname)
+    }
+
+    /// check if `rest` could have been rendered from `segments`
+    fn matches_segments(segments: &[Segment], rest: &str, name: &str, hostname: &str) -> bool {
+        let Some((segment, segments)) = segments.split_first() else {
+            return rest.is_empty();
+        };
+        let fixed = match segment {
+            Segment::Literal(text) => Some(text.as_str()),
+            Segment::Crate => Some(name),
+            Segment::Hostname => Some(hostname),
+            Segment::Date | Segment::Time | Segment::Pid => None,
+        };
+        if let Some(fixed) = fixed {
+            return rest
+                .strip_prefix(fixed)
+                .is_some_and(|rest| Self::matches_segments(segments, rest, name, hostname));
+        }
+        let allowed =
+            |c: char| c.is_ascii_digit() || (*segment != Segment::Pid && matches!(c, '-' | '_'));
+        let len = rest.find(|c| !allowed(c)).unwrap_or(rest.len());
+        // the value might be followed by a literal that looks like it, so try all lengths
+        (1..=len)
+            .rev()
+            .any(|n| Self::matches_segments(segments, &rest[n..], name, hostname))
+    }
+}
+
+/// Where the logfiles of a [`LogfileWriter`] are, how they are named and which are kept
+#[derive(Debug, Clone)]
+struct Logfiles {
+    /// logfiles are created here
+    dir: PathBuf,
+    /// the name of the crate that logs
+    name: String,
+    /// how the names of the logfiles are made
+    template: LogfileTemplate,
+    /// which old logfiles are kept
+    retention: Retention,
+}
+
+impl Logfiles {
+    /// path of the logfile for a [Rotation] period that starts at `now`
+    fn path(&self, rotation: Rotation, now: DateTime<Local>) -> PathBuf {
+        self.dir
+            .join(self.template.render(&self.name, rotation, now))
+    }
+
+    /// check if `filename` is the name of one of these logfiles, possibly an old one of a size
+    /// based [Rotation] or a compressed one
+    fn is_logfile(&self, filename: &str, hostname: &str) -> bool {
+        let filename = COMPRESSED_EXTENSIONS
+            .iter()
+            .find_map(|extension| filename.strip_suffix(extension))
+            .unwrap_or(filename);
+        let numbered = filename
+            .rsplit_once('.')
+            .filter(|(_, n)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            .map(|(base, _)| base);
+        [Some(filename), numbered]
+            .into_iter()
+            .flatten()
+            .any(|name| self.template.matches(name, &self.name, hostname))
+    }
+
+    /// the finished logfiles that are not compressed yet, like those an earlier run left behind
+    ///
+    /// Of a size based [Rotation], only the numbered old logfiles next to the `current` one are
+    /// finished. Of a time based one, only logfiles last written in an earlier period than `now`
+    /// are, as other instances might still write into the others.
+    fn uncompressed(
+        &self,
+        rotation: Rotation,
+        current: &Path,
+        now: DateTime<Local>,
+    ) -> io::Result<Vec<PathBuf>> {
+        let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+        let current_name = current.file_name().and_then(|name| name.to_str());
+        let mut finished = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let Some(filename) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if path == current
+                || COMPRESSED_EXTENSIONS
+                    .iter()
+                    .any(|extension| filename.ends_with(extension))
+                || !self.is_logfile(&filename, &hostname)
+            {
+                continue;
+            }
+            // another instance might have deleted it in the meantime
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let done = match rotation {
+                Rotation::Never => false,
+                Rotation::Size { .. } => current_name
+                    .and_then(|name| filename.strip_prefix(name))
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())),
+                Rotation::Daily | Rotation::Hourly => metadata
+                    .modified()
+                    .is_ok_and(|modified| rotation.period(modified.into()) != rotation.period(now)),
+            };
+            if done && metadata.is_file() {
+                finished.push(path);
+            }
+        }
+        Ok(finished)
+    }
+
+    /// delete old logfiles according to the [Retention]
+    ///
+    /// Only files whose names could have been made from the template for this crate are touched,
+    /// and never the `current` logfile.
+    fn cleanup(&self, current: &Path) -> io::Result<()> {
+        if self.retention.keeps_everything() {
+            return Ok(());
+        }
+        let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+        let mut old = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path == current
+                || !entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|filename| self.is_logfile(filename, &hostname))
+            {
+                continue;
+            }
+            // another instance might have deleted it in the meantime
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_file() {
+                old.push((metadata.modified()?, path));
+            }
+        }
+        // newest first
+        old.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        let now = SystemTime::now();
+        let keep = self
+            .retention
+            .max_files
+            .map_or(usize::MAX, |max| max.saturating_sub(1));
+        for (i, (modified, path)) in old.into_iter().enumerate() {
+            let expired = self
+                .retention
+                .max_age
+                .is_some_and(|max_age| now.duration_since(modified).is_ok_and(|age| age > max_age));
+            if i >= keep || expired {
+                match fs::remove_file(path) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                    _ => (),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A writer for logfiles that rolls over to new files according to a [`Rotation`]
+#[derive(Debug)]
+pub(crate) struct LogfileWriter {
+    /// where the logfiles are and how they are named
+    files: Logfiles,
+    /// when to roll over to a new logfile
+    rotation: Rotation,
+    /// how finished logfiles are compressed
+    compression: Compression,
+    /// append to existing logfiles instead of truncating them
+    append: bool,
+    /// the currently opened logfile
+    file: BufWriter<File>,
+    /// path of the currently opened logfile
+    path: PathBuf,
+    /// how many bytes were written to the current logfile
+    size: u64,
+    /// when to roll over next, if the [Rotation] is time based
+    next_rollover: Option<DateTime<Local>>,
+    /// the background thread compressing the last finished logfile
+    compressing: Option<thread::JoinHandle<()>>,
+}
+
+impl LogfileWriter {
+    /// create `dir` if needed, open the first logfile in it and delete old logfiles according to
+    /// the `retention`
+    pub(crate) fn new(
+        dir: PathBuf,
+        name: String,
+        template: LogfileTemplate,
+        rotation: Rotation,
+        retention: Retention,
+        compression: Compression,
+        append: bool,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let files = Logfiles {
+            dir,
+            name,
+            template,
+            retention,
+        };
+        let now = Local::now();
+        let path = files.path(rotation, now);
+        let (file, size) = Self::open(&path, append)?;
+        files.cleanup(&path)?;
+        let uncompressed = if compression == Compression::None {
+            Vec::new()
+        } else {
+            files.uncompressed(rotation, &path, now)?
+        };
+        let mut writer = Self {
+            file,
+            path,
+            size,
+            next_rollover: rotation.next_rollover(now),
+            files,
+            rotation,
+            compression,
+            append,
+            compressing: None,
+        };
+        if !uncompressed.is_empty() {
+            writer.finish(uncompressed);
+        }
+        Ok(writer)
+    }
+
+    /// open the logfile at `path`, returning it with its current size
+    fn open(path: &Path, append: bool) -> io::Result<(BufWriter<File>, u64)> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        let size = file.metadata()?.len();
+        Ok((BufWriter::new(file), size))
+    }
+
+    /// check if writing `incoming` more bytes requires a new logfile first
+    fn rollover_due(&self, incoming: usize) -> bool {
+        match self.rotation {
+            Rotation::Never => false,
+            Rotation::Daily | Rotation::Hourly => {
+                self.next_rollover.is_some_and(|next| Local::now() >= next)
+            }
+            Rotation::Size { max_bytes, .. } => {
+                // never roll over an empty file, a single huge event would otherwise loop
+                self.size > 0 && self.size.saturating_add(incoming as u64) > max_bytes
+            }
+        }
+    }
+
+    /// close the current logfile and open the next one
+    ///
+    /// The finished logfile is compressed and old logfiles are deleted afterwards.
+    fn roll(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        // the old logfiles must stay where they are while they are compressed
+        self.wait_for_compression();
+        let now = Local::now();
+        let (finished, truncate) = if let Rotation::Size { keep, .. } = self.rotation {
+            self.shift_old_files(keep)?;
+            // the full logfile is not moved away if no old logfiles are kept
+            ((keep > 0).then(|| self.numbered(1)), keep == 0)
+        } else {
+            let next = self.files.path(self.rotation, now);
+            let finished = std::mem::replace(&mut self.path, next);
+            ((finished != self.path).then_some(finished), false)
+        };
+        (self.file, self.size) = Self::open(&self.path, self.append && !truncate)?;
+        self.next_rollover = self.rotation.next_rollover(now);
+        self.finish(finished.into_iter().collect());
+        Ok(())
+    }
+
+    /// compress the `finished` logfiles, then delete old logfiles
+    ///
+    /// With [compression](Compression), this happens in a background thread.
+    fn finish(&mut self, finished: Vec<PathBuf>) {
+        let files = self.files.clone();
+        let current = self.path.clone();
+        let compression = self.compression;
+        // the current logfile is open, errors from here on must not lose the event that is
+        // written next, and there is nowhere to report them to
+        let finish = move || {
+            for finished in finished {
+                let _ = compression.compress(&finished);
+            }
+            let _ = files.cleanup(&current);
+        };
+        if self.compression == Compression::None {
+            finish();
+        } else {
+            self.compressing = thread::Builder::new()
+                .name("libpt-log-compress".to_string())
+                .spawn(finish)
+                .ok();
+        }
+    }
+
+    /// wait until the last finished logfile is compressed
+    pub(crate) fn wait_for_compression(&mut self) {
+        if let Some(compressing) = self.compressing.take() {
+            let _ = compressing.join();
+        }
+    }
+
+    /// path of the `n`th old logfile of a size based [Rotation]
+    fn numbered(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    /// move `{name}.log` to `{name}.log.1`, `{name}.log.1` to `{name}.log.2` and so on, keeping
+    /// at most `keep` old logfiles
+    ///
+    /// Compressed old logfiles are moved the same way, keeping their extension.
+    fn shift_old_files(&self, keep: usize) -> io::Result<()> {
+        if keep == 0 {
+            return Ok(());
+        }
+        let with_extension = |path: PathBuf, extension: &str| {
+            let mut path = path.into_os_string();
+            path.push(extension);
+            PathBuf::from(path)
+        };
+        for extension in std::iter::once("").chain(COMPRESSED_EXTENSIONS) {
+            let oldest = with_extension(self.numbered(keep), extension);
+            if oldest.exists() {
+                fs::remove_file(oldest)?;
+            }
+            for n in (1..keep).rev() {
+                let from = with_extension(self.numbered(n), extension);
+                if from.exists() {
+                    fs::rename(from, with_extension(self.numbered(n + 1), extension))?;
+                }
+            }
+        }
+        fs::rename(&self.path, self.numbered(1))
+    }
+}
+
+impl Write for LogfileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.rollover_due(buf.len()) {
+            self.roll()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// A message for the background thread of a non-blocking logfile
+#[derive(Debug)]
+pub(crate) enum Message {
+    /// write a formatted event
+    Write(Vec<u8>),
+    /// flush everything written so far, then acknowledge
+    Flush(mpsc::SyncSender<()>),
+}
+
+/// A shared handle to the logfile of a [Logger](crate::Logger)
+///
+/// The layer writing to the logfile and the [Logger](crate::Logger) itself both hold one, so the
+/// [Logger](crate::Logger) can flush the logfile.
+#[derive(Debug, Clone)]
+pub(crate) enum LogfileHandle {
+    /// events are written by the thread that logs them
+    Blocking(Arc<Mutex<LogfileWriter>>),
+    /// events are sent to a background thread that writes them
+    NonBlocking(mpsc::SyncSender<Message>),
+}
+
+impl LogfileHandle {
+    /// write events directly from the threads that log them
+    pub(crate) fn blocking(writer: LogfileWriter) -> Self {
+        Self::Blocking(Arc::new(Mutex::new(writer)))
+    }
+
+    /// move the `writer` to a background thread and send events there
+    ///
+    /// The thread ends once all handles to it are dropped.
+    pub(crate) fn non_blocking(writer: LogfileWriter) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::sync_channel(NON_BLOCKING_CAPACITY);
+        thread::Builder::new()
+            .name("libpt-log-file".to_string())
+            .spawn(move || Self::work(writer, &receiver))?;
+        Ok(Self::NonBlocking(sender))
+    }
+
+    /// the loop of the background thread of a non-blocking logfile
+    fn work(mut writer: LogfileWriter, receiver: &mpsc::Receiver<Message>) {
+        // there is nowhere left to report errors to, so they are ignored here
+        let handle = |writer: &mut LogfileWriter, message| match message {
+            Message::Write(buf) => {
+                let _ = writer.write_all(&buf);
+            }
+            Message::Flush(ack) => {
+                let _ = writer.flush();
+                writer.wait_for_compression();
+                let _ = ack.send(());
+            }
+        };
+        while let Ok(message) = receiver.recv() {
+            handle(&mut writer, message);
+            // write everything that is already waiting before flushing
+            while let Ok(message) = receiver.try_recv() {
+                handle(&mut writer, message);
+            }
+            let _ = writer.flush();
+        }
+    }
+
+    /// make sure everything logged so far is written to the logfile
+    ///
+    /// For a non-blocking logfile, this waits until the background thread has caught up. It also
+    /// waits until the last finished logfile is compressed.
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        match self {
+            Self::Blocking(writer) => {
+                let mut writer = writer.lock().unwrap_or_else(PoisonError::into_inner);
+                writer.flush()?;
+                writer.wait_for_compression();
+                drop(writer);
+                Ok(())
+            }
+            Self::NonBlocking(sender) => {
+                let (ack, done) = mpsc::sync_channel(1);
+                sender
+                    .send(Message::Flush(ack))
+                    .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+                done.recv()
+                    .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+            }
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogfileHandle {
+    type Writer = LogfileHandleWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        match self {
+            Self::Blocking(writer) => {
+                LogfileHandleWriter::Blocking(writer.lock().unwrap_or_else(PoisonError::into_inner))
+            }
+            Self::NonBlocking(sender) => LogfileHandleWriter::NonBlocking(sender),
+        }
+    }
+}
+
+/// Writes one event to a [`LogfileHandle`]
+#[derive(Debug)]
+pub(crate) enum LogfileHandleWriter<'a> {
+    /// the logfile, locked for this thread
+    Blocking(MutexGuard<'a, LogfileWriter>),
+    /// the way to the background thread
+    NonBlocking(&'a mpsc::SyncSender<Message>),
+}
+
+impl Write for LogfileHandleWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            // every event is flushed right away, so nothing is lost if the program crashes
+            Self::Blocking(writer) => {
+                writer.write_all(buf)?;
+                writer.flush()?;
+            }
+            Self::NonBlocking(sender) => sender
+                .send(Message::Write(buf.to_vec()))
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Blocking(writer) => writer.flush(),
+            Self::NonBlocking(_) => Ok(()),
+        }
+    }
+}
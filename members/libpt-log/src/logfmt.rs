@@ -0,0 +1,184 @@
+//! # logfmt output for [`pt-log`](crate)
+//!
+//! This module writes events as `key=value` pairs, for [`LogFormat::Logfmt`]. Values are quoted
+//! when they are empty or contain spaces, `=`, quotes, backslashes or control characters, and
+//! quotes, backslashes and control characters inside of them are escaped.
+//!
+//! [`LogFormat::Logfmt`]: crate::LogFormat::Logfmt
+
+use std::fmt;
+
+use tracing::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{
+    field::RecordFields,
+    fmt::{
+        format::Writer, time::FormatTime, FmtContext, FormatEvent, FormatFields, FormattedFields,
+    },
+    registry::LookupSpan,
+};
+
+/// Formats events as logfmt lines
+#[derive(Debug)]
+pub struct Logfmt<T> {
+    /// how the `ts` key is written
+    timer: T,
+}
+
+impl<T: FormatTime> Logfmt<T> {
+    /// create a format with timestamps from `timer`
+    pub const fn new(timer: T) -> Self {
+        Self { timer }
+    }
+}
+
+impl<S, N, T> FormatEvent<S, N> for Logfmt<T>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+    T: FormatTime,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        let mut time = String::new();
+        self.timer.format_time(&mut Writer::new(&mut time))?;
+        // the uptime is padded with spaces
+        let time = time.trim();
+        if !time.is_empty() {
+            writer.write_str("ts=")?;
+            write_value(&mut writer, time)?;
+            writer.write_char(' ')?;
+        }
+        write!(writer, "level={}", meta.level().as_str().to_lowercase())?;
+        writer.write_str(" target=")?;
+        write_value(&mut writer, meta.target())?;
+        if let Some(scope) = ctx.event_scope() {
+            let names: Vec<&str> = scope.from_root().map(|span| span.name()).collect();
+            writer.write_str(" span=")?;
+            write_value(&mut writer, &names.join(":"))?;
+        }
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                if let Some(fields) = span.extensions().get::<FormattedFields<N>>() {
+                    if !fields.is_empty() {
+                        write!(writer, " {fields}")?;
+                    }
+                }
+            }
+        }
+        let mut visitor = Visitor::new(writer.by_ref(), true);
+        event.record(&mut visitor);
+        visitor.result?;
+        writeln!(writer)
+    }
+}
+
+/// Formats the fields of spans as logfmt pairs
+///
+/// They are stored with the span, so that [`Logfmt`] can add them to the events in it.
+#[derive(Debug, Default)]
+pub struct LogfmtFields;
+
+impl<'writer> FormatFields<'writer> for LogfmtFields {
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> fmt::Result {
+        let mut visitor = Visitor::new(writer, false);
+        fields.record(&mut visitor);
+        visitor.result
+    }
+}
+
+/// Writes fields as `key=value` pairs, separated by spaces
+struct Visitor<'writer> {
+    /// where the pairs are written
+    writer: Writer<'writer>,
+    /// put a space before the next pair
+    separate: bool,
+    /// the first error while writing
+    result: fmt::Result,
+}
+
+impl<'writer> Visitor<'writer> {
+    /// write pairs to `writer`, starting with a space if `separate` is set
+    const fn new(writer: Writer<'writer>, separate: bool) -> Self {
+        Self {
+            writer,
+            separate,
+            result: Ok(()),
+        }
+    }
+
+    /// write one pair, unless writing already failed
+    fn pair(&mut self, field: &Field, value: &str) {
+        if self.result.is_ok() {
+            self.result = self.write_pair(field, value);
+        }
+    }
+
+    /// write one pair
+    fn write_pair(&mut self, field: &Field, value: &str) -> fmt::Result {
+        if self.separate {
+            self.writer.write_char(' ')?;
+        }
+        self.separate = true;
+        write_key(&mut self.writer, field.name())?;
+        self.writer.write_char('=')?;
+        write_value(&mut self.writer, value)
+    }
+}
+
+impl Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.pair(field, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.pair(field, &format!("{value:?}"));
+    }
+}
+
+/// write a key, renaming the message of an event to `msg` and replacing characters that would
+/// break the pair
+fn write_key(writer: &mut impl fmt::Write, key: &str) -> fmt::Result {
+    if key == "message" {
+        return writer.write_str("msg");
+    }
+    for c in key.chars() {
+        if c == ' ' || c == '=' || c == '"' || c.is_control() {
+            writer.write_char('_')?;
+        } else {
+            writer.write_char(c)?;
+        }
+    }
+    Ok(())
+}
+
+/// write a value, quoted and escaped if needed
+fn write_value(writer: &mut impl fmt::Write, value: &str) -> fmt::Result {
+    let quote = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control());
+    if !quote {
+        return writer.write_str(value);
+    }
+    writer.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            '\n' => writer.write_str("\\n")?,
+            '\r' => writer.write_str("\\r")?,
+            '\t' => writer.write_str("\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", u32::from(c))?,
+            c => writer.write_char(c)?,
+        }
+    }
+    writer.write_char('"')
+}
@@ -0,0 +1,444 @@
+//! # Network output for [`pt-log`](crate)
+//!
+//! This module streams events to a log collector over TCP or UDP, see
+//! [`LoggerBuilder::log_to_network`](crate::LoggerBuilder::log_to_network).
+//!
+//! The events are sent from a background thread, so a slow or unreachable collector never
+//! blocks the program. While the collector is unreachable, events are buffered and the thread
+//! keeps reconnecting. If it stays unreachable, or the buffer runs full, the events are written
+//! to the logfile instead, if there is one.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    net::{TcpStream, ToSocketAddrs, UdpSocket},
+    sync::mpsc::{self, RecvTimeoutError, TrySendError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::DateTime;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::{
+    logfile::{LogfileHandle, Message},
+    syslog::severity,
+};
+
+/// The address of the collector used when none is given, the usual GELF port on this machine
+pub const DEFAULT_NETWORK_ADDRESS: &str = "localhost:12201";
+
+/// How many events are buffered by default while the collector is unreachable
+pub const DEFAULT_NETWORK_BUFFER: usize = 1024;
+
+/// How long the collector may be unreachable by default before events go to the logfile
+pub const DEFAULT_NETWORK_FALLBACK: Duration = Duration::from_secs(30);
+
+/// How long connecting to the collector or sending to it may take
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long to wait before reconnecting for the first time, doubled after each failure
+const MIN_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The longest time to wait before reconnecting
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// The transport used to reach the collector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    /// a TCP connection, which notices when the collector goes away
+    #[default]
+    Tcp,
+    /// one UDP datagram per event
+    ///
+    /// Events that do not fit into a single datagram are lost.
+    Udp,
+}
+
+/// How the events are encoded for the collector
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framing {
+    /// one JSON object per event, like [`LogFormat::Json`](crate::LogFormat::Json)
+    ///
+    /// Over TCP, the objects are separated by newlines.
+    #[default]
+    Json,
+    /// the [Graylog Extended Log Format](https://go2docs.graylog.org/current/getting_in_log_data/gelf.html)
+    ///
+    /// The message becomes the `short_message`, the level its
+    /// [syslog severity](crate::syslog::severity), and all other fields, the target and the spans
+    /// become additional fields starting with `_`. Over TCP, the messages are separated by null
+    /// bytes.
+    ///
+    /// ```json
+    /// {"_answer":42,"_target":"app","host":"myhost","level":6,"short_message":"hello","timestamp":1725197820.0,"version":"1.1"}
+    /// ```
+    Gelf,
+}
+
+/// The settings of the network sink of a [`LoggerBuilder`](crate::LoggerBuilder)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Network {
+    /// send events to the collector
+    pub(crate) enabled: bool,
+    /// the address of the collector, like `logs.example.com:12201`
+    pub(crate) address: String,
+    /// the transport
+    pub(crate) protocol: Protocol,
+    /// the encoding of the events
+    pub(crate) framing: Framing,
+    /// the maximum verbosity level, that of the terminal if [None]
+    pub(crate) level: Option<LevelFilter>,
+    /// how many events are buffered while the collector is unreachable
+    pub(crate) buffer: usize,
+    /// how long the collector may be unreachable before events go to the logfile
+    pub(crate) fallback_after: Duration,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: DEFAULT_NETWORK_ADDRESS.to_string(),
+            protocol: Protocol::default(),
+            framing: Framing::default(),
+            level: None,
+            buffer: DEFAULT_NETWORK_BUFFER,
+            fallback_after: DEFAULT_NETWORK_FALLBACK,
+        }
+    }
+}
+
+impl Network {
+    /// start the background thread that sends the events
+    ///
+    /// Events that can not be delivered are written to the `fallback`, if there is one. The
+    /// collector is only contacted once there is something to send.
+    pub(crate) fn start(&self, fallback: Option<LogfileHandle>) -> io::Result<NetworkHandle> {
+        let (sender, receiver) = mpsc::sync_channel(self.buffer);
+        let worker = Worker {
+            settings: self.clone(),
+            hostname: gethostname::gethostname().to_string_lossy().into_owned(),
+            connection: None,
+            pending: VecDeque::new(),
+            down_since: None,
+            retry_at: Instant::now(),
+            backoff: MIN_BACKOFF,
+            fallback: fallback.clone(),
+        };
+        thread::Builder::new()
+            .name("libpt-log-network".to_string())
+            .spawn(move || worker.work(&receiver))?;
+        Ok(NetworkHandle { sender, fallback })
+    }
+}
+
+/// A shared handle to the background thread of the network sink
+///
+/// The layer sending the events and the [Logger](crate::Logger) itself both hold one, so the
+/// [Logger](crate::Logger) can flush the sink.
+#[derive(Debug, Clone)]
+pub(crate) struct NetworkHandle {
+    /// the way to the background thread
+    sender: mpsc::SyncSender<Message>,
+    /// where events go if the background thread can not take them
+    fallback: Option<LogfileHandle>,
+}
+
+impl NetworkHandle {
+    /// wait until the background thread has tried to send everything logged so far
+    ///
+    /// This does not wait for an unreachable collector, the events stay buffered or go to the
+    /// logfile as usual.
+    pub(crate) fn flush(&self) -> io::Result<()> {
+        let (ack, done) = mpsc::sync_channel(1);
+        self.sender
+            .send(Message::Flush(ack))
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        done.recv()
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+
+    /// hand an event to the background thread, or write it to the logfile if it is too busy
+    fn send(&self, event: Vec<u8>) -> io::Result<()> {
+        let (TrySendError::Full(message) | TrySendError::Disconnected(message)) =
+            match self.sender.try_send(Message::Write(event)) {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+        if let (Message::Write(event), Some(fallback)) = (message, &self.fallback) {
+            fallback.make_writer().write_all(&event)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for NetworkHandle {
+    type Writer = NetworkWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        NetworkWriter {
+            handle: self,
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Collects one formatted event and hands it to the background thread when dropped
+#[derive(Debug)]
+pub(crate) struct NetworkWriter<'a> {
+    /// where the event goes
+    handle: &'a NetworkHandle,
+    /// the event so far
+    buf: Vec<u8>,
+}
+
+impl Write for NetworkWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for NetworkWriter<'_> {
+    fn drop(&mut self) {
+        if !self.buf.is_empty() {
+            // there is nowhere to report a failure to log
+            let _ = self.handle.send(std::mem::take(&mut self.buf));
+        }
+    }
+}
+
+/// A connection to the collector
+#[derive(Debug)]
+enum Connection {
+    /// over TCP
+    Tcp(TcpStream),
+    /// over UDP, connected to the collector
+    Udp(UdpSocket),
+}
+
+impl Connection {
+    /// connect to the first address of the collector that works
+    fn open(address: &str, protocol: Protocol) -> io::Result<Self> {
+        let mut last_error = io::Error::from(io::ErrorKind::AddrNotAvailable);
+        for addr in address.to_socket_addrs()? {
+            let connection = match protocol {
+                Protocol::Tcp => {
+                    TcpStream::connect_timeout(&addr, NETWORK_TIMEOUT).and_then(|stream| {
+                        stream.set_write_timeout(Some(NETWORK_TIMEOUT))?;
+                        Ok(Self::Tcp(stream))
+                    })
+                }
+                Protocol::Udp => {
+                    let local = if addr.is_ipv4() {
+                        "0.0.0.0:0"
+                    } else {
+                        "[::]:0"
+                    };
+                    UdpSocket::bind(local).and_then(|socket| {
+                        socket.connect(addr)?;
+                        Ok(Self::Udp(socket))
+                    })
+                }
+            };
+            match connection {
+                Ok(connection) => return Ok(connection),
+                Err(err) => last_error = err,
+            }
+        }
+        Err(last_error)
+    }
+
+    /// send one encoded message
+    fn send(&mut self, message: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.write_all(message),
+            Self::Udp(socket) => socket.send(message).map(|_| ()),
+        }
+    }
+}
+
+/// The state of the background thread of the network sink
+struct Worker {
+    /// how and where to send
+    settings: Network,
+    /// the name of this machine, for GELF
+    hostname: String,
+    /// the connection to the collector, if there is one
+    connection: Option<Connection>,
+    /// the formatted events that were not delivered yet, oldest first
+    pending: VecDeque<Vec<u8>>,
+    /// when the collector became unreachable, [None] while it is reachable
+    down_since: Option<Instant>,
+    /// when to try connecting again
+    retry_at: Instant,
+    /// how long to wait after the next failure
+    backoff: Duration,
+    /// where undeliverable events go
+    fallback: Option<LogfileHandle>,
+}
+
+impl Worker {
+    /// the loop of the background thread
+    ///
+    /// The thread ends once all handles to it are dropped, after a last try to deliver what is
+    /// left.
+    fn work(mut self, receiver: &mpsc::Receiver<Message>) {
+        loop {
+            // wake up regularly to reconnect, even if nothing new is logged
+            match receiver.recv_timeout(self.backoff) {
+                Ok(Message::Write(event)) => self.push(event),
+                Ok(Message::Flush(ack)) => {
+                    self.deliver();
+                    let _ = ack.send(());
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            // take everything that is already waiting before sending
+            while let Ok(message) = receiver.try_recv() {
+                match message {
+                    Message::Write(event) => self.push(event),
+                    Message::Flush(ack) => {
+                        self.deliver();
+                        let _ = ack.send(());
+                    }
+                }
+            }
+            self.deliver();
+        }
+        self.retry_at = Instant::now();
+        self.deliver();
+        let pending = self.pending.len();
+        self.spill(pending);
+    }
+
+    /// buffer an event, making room by writing the oldest one to the logfile if needed
+    fn push(&mut self, event: Vec<u8>) {
+        if self.pending.len() >= self.settings.buffer {
+            self.spill(1);
+        }
+        self.pending.push_back(event);
+    }
+
+    /// send the buffered events, as long as the collector takes them
+    fn deliver(&mut self) {
+        while let Some(event) = self.pending.front() {
+            let message = self.encode(event);
+            if self.connection.is_none() {
+                if Instant::now() < self.retry_at {
+                    break;
+                }
+                self.connection =
+                    Connection::open(&self.settings.address, self.settings.protocol).ok();
+            }
+            let sent = self
+                .connection
+                .as_mut()
+                .is_some_and(|connection| connection.send(&message).is_ok());
+            if !sent {
+                self.connection = None;
+                self.failed();
+                break;
+            }
+            self.pending.pop_front();
+            self.down_since = None;
+            self.backoff = MIN_BACKOFF;
+        }
+        if self
+            .down_since
+            .is_some_and(|since| since.elapsed() >= self.settings.fallback_after)
+        {
+            let pending = self.pending.len();
+            self.spill(pending);
+        }
+    }
+
+    /// remember that the collector is unreachable and when to try again
+    fn failed(&mut self) {
+        let now = Instant::now();
+        self.down_since.get_or_insert(now);
+        self.retry_at = now + self.backoff;
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+    }
+
+    /// write the `count` oldest buffered events to the logfile, or drop them if there is none
+    fn spill(&mut self, count: usize) {
+        for event in self.pending.drain(..count) {
+            if let Some(fallback) = &self.fallback {
+                // there is nowhere left to report errors to
+                let _ = fallback.make_writer().write_all(&event);
+            }
+        }
+    }
+
+    /// encode a formatted event for the collector
+    fn encode(&self, event: &[u8]) -> Vec<u8> {
+        let line = event.strip_suffix(b"\n").unwrap_or(event);
+        let mut message = match self.settings.framing {
+            Framing::Json => line.to_vec(),
+            Framing::Gelf => gelf(line, &self.hostname),
+        };
+        match (self.settings.framing, self.settings.protocol) {
+            (_, Protocol::Udp) => (),
+            (Framing::Json, Protocol::Tcp) => message.push(b'\n'),
+            (Framing::Gelf, Protocol::Tcp) => message.push(b'\0'),
+        }
+        message
+    }
+}
+
+/// turn an event formatted as [JSON](crate::LogFormat::Json) into a GELF message
+fn gelf(event: &[u8], hostname: &str) -> Vec<u8> {
+    let mut fields: Map<String, Value> = serde_json::from_slice(event).unwrap_or_default();
+    let mut message = Map::new();
+    message.insert("version".to_string(), "1.1".into());
+    message.insert("host".to_string(), hostname.into());
+    message.insert(
+        "short_message".to_string(),
+        fields.remove("message").unwrap_or_else(|| "".into()),
+    );
+    if let Some(timestamp) = fields
+        .remove("timestamp")
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+    {
+        #[allow(clippy::cast_precision_loss)] // microseconds since 1970 still fit into an f64
+        let seconds = timestamp.timestamp_micros() as f64 / 1_000_000.0;
+        message.insert("timestamp".to_string(), seconds.into());
+    }
+    if let Some(level) = fields
+        .remove("level")
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(|level| level.parse().ok())
+    {
+        message.insert("level".to_string(), severity(level).into());
+    }
+    for (key, value) in fields {
+        // GELF reserves `_id`, and only allows strings and numbers as values
+        let key = if key == "id" {
+            "__id".to_string()
+        } else {
+            format!("_{key}")
+        };
+        let value = match value {
+            Value::String(_) | Value::Number(_) => value,
+            other => other.to_string().into(),
+        };
+        message.insert(key, value);
+    }
+    Value::Object(message).to_string().into_bytes()
+}
@@ -0,0 +1,42 @@
+//! # Panic hook for [`pt-log`](crate)
+//!
+//! This module makes sure that panics end up in the logs, including the logfile, and not only on
+//! the stderr.
+
+use std::backtrace::Backtrace;
+
+use crate::{error, logfile::LogfileHandle, recent::RecentEvents};
+
+/// install a panic hook that logs panics before passing them on to the previous hook
+///
+/// If `recent` events are kept, they are dumped after the panic was logged. The `logfile` is
+/// flushed afterwards, so that the panic is written even if the program ends right after.
+pub fn install_hook(logfile: Option<LogfileHandle>, recent: Option<RecentEvents>) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let thread = std::thread::current();
+        let location = info
+            .location()
+            .map_or_else(|| "<unknown>".to_string(), ToString::to_string);
+        error!(
+            thread = thread.name().unwrap_or("<unnamed>"),
+            location,
+            backtrace = %Backtrace::force_capture(),
+            "panicked: {message}"
+        );
+        // we are already panicking, there is nothing else to do about errors
+        if let Some(recent) = &recent {
+            let _ = recent.dump(logfile.as_ref());
+        }
+        if let Some(logfile) = &logfile {
+            let _ = logfile.flush();
+        }
+        previous(info);
+    }));
+}
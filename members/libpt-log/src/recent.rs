@@ -0,0 +1,108 @@
+//! # Recent events for [`pt-log`](crate)
+//!
+//! This module keeps the most recent events in memory, at a more verbose level than the sinks, so
+//! that the context of a failure can be written out after it happened.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::logfile::LogfileHandle;
+
+/// A ring buffer with the most recent formatted events
+#[derive(Debug, Clone)]
+pub struct RecentEvents {
+    /// how many events are kept
+    capacity: usize,
+    /// the formatted events, oldest first
+    events: Arc<Mutex<VecDeque<Vec<u8>>>>,
+}
+
+impl RecentEvents {
+    /// create an empty ring buffer for `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// add a formatted event, dropping the oldest one if the buffer is full
+    fn push(&self, event: Vec<u8>) {
+        let mut events = self.events.lock().unwrap_or_else(PoisonError::into_inner);
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// take all events out of the buffer, with a heading
+    fn take(&self) -> Vec<u8> {
+        let events: Vec<_> = self
+            .events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain(..)
+            .collect();
+        let mut out = format!("---- {} most recent events ----\n", events.len()).into_bytes();
+        for event in events {
+            out.extend(event);
+        }
+        out
+    }
+
+    /// write all events to the `logfile`, or to the stderr if there is none, and clear the buffer
+    pub fn dump(&self, logfile: Option<&LogfileHandle>) -> io::Result<()> {
+        let out = self.take();
+        match logfile {
+            Some(logfile) => {
+                logfile.make_writer().write_all(&out)?;
+                logfile.flush()
+            }
+            None => io::stderr().lock().write_all(&out),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for RecentEvents {
+    type Writer = RecentEventWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RecentEventWriter {
+            recent: self,
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Collects one formatted event and adds it to the [`RecentEvents`] when dropped
+#[derive(Debug)]
+pub struct RecentEventWriter<'a> {
+    /// where the event goes
+    recent: &'a RecentEvents,
+    /// the event so far
+    buf: Vec<u8>,
+}
+
+impl Write for RecentEventWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RecentEventWriter<'_> {
+    fn drop(&mut self) {
+        if !self.buf.is_empty() {
+            self.recent.push(std::mem::take(&mut self.buf));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! # Terminal streams for [`pt-log`](crate)
+//!
+//! This module decides to which standard stream the events shown on the terminal are written.
+
+use serde::Deserialize;
+use tracing::Level;
+use tracing_subscriber::fmt::writer::{BoxMakeWriter, MakeWriterExt};
+
+/// The standard streams the events shown on the terminal are written to
+///
+/// Writing diagnostics to the stderr keeps the stdout clean for the actual output of a program,
+/// so that it can be piped into other programs, like `mytool | jq`.
+///
+/// See [`LoggerBuilder::terminal_stream`](crate::LoggerBuilder::terminal_stream).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalStream {
+    /// write all events to the stdout
+    #[default]
+    Stdout,
+    /// write all events to the stderr
+    Stderr,
+    /// write events at `threshold` or above to the stderr, and all others to the stdout
+    ///
+    /// With a `threshold` of [`Level::WARN`], warnings and errors go to the stderr while
+    /// everything else goes to the stdout.
+    Split {
+        /// the least severe level that is written to the stderr
+        #[serde(deserialize_with = "crate::config::deserialize_threshold")]
+        threshold: Level,
+    },
+}
+
+impl TerminalStream {
+    /// Split the events so that warnings and errors go to the stderr, the rest to the stdout
+    pub const WARNINGS_TO_STDERR: Self = Self::Split {
+        threshold: Level::WARN,
+    };
+
+    /// create the writer for the terminal sink
+    pub(crate) fn make_writer(self) -> BoxMakeWriter {
+        match self {
+            Self::Stdout => BoxMakeWriter::new(std::io::stdout),
+            Self::Stderr => BoxMakeWriter::new(std::io::stderr),
+            // `with_max_level` keeps the events that are at least as severe as `threshold`
+            Self::Split { threshold } => BoxMakeWriter::new(
+                std::io::stderr
+                    .with_max_level(threshold)
+                    .or_else(std::io::stdout),
+            ),
+        }
+    }
+}
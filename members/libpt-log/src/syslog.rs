@@ -0,0 +1,324 @@
+//! # Syslog output for [`pt-log`](crate)
+//!
+//! This module sends events to the local syslog daemon over a Unix datagram socket, see
+//! [`LoggerBuilder::log_to_syslog`](crate::LoggerBuilder::log_to_syslog). The sink is only
+//! available on Unix, but the [severity] of levels is also used by the
+//! [network sink](crate::network) on all platforms.
+
+#[cfg(unix)]
+use std::{
+    io::{self, Write},
+    os::unix::net::UnixDatagram,
+    path::PathBuf,
+    sync::Arc,
+};
+
+#[cfg(unix)]
+use chrono::{Local, SecondsFormat};
+use serde::Deserialize;
+use tracing::Level;
+#[cfg(unix)]
+use tracing::{level_filters::LevelFilter, Metadata};
+#[cfg(unix)]
+use tracing_subscriber::fmt::MakeWriter;
+
+/// The socket of the syslog daemon used when none is given
+pub const DEFAULT_SYSLOG_PATH: &str = "/dev/log";
+
+/// The format of the messages sent to the syslog daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogFormat {
+    /// the current syslog protocol, as defined in [RFC 5424](https://www.rfc-editor.org/rfc/rfc5424)
+    ///
+    /// ```text
+    /// <14>1 2024-09-01T13:37:00.000000+02:00 myhost myapp 1234 - - hello
+    /// ```
+    #[default]
+    Rfc5424,
+    /// the older BSD syslog format, as described in [RFC 3164](https://www.rfc-editor.org/rfc/rfc3164)
+    ///
+    /// ```text
+    /// <14>Sep  1 13:37:00 myhost myapp[1234]: hello
+    /// ```
+    Rfc3164,
+}
+
+/// The facility of the messages, which tells the syslog daemon what kind of program sent them
+///
+/// The numeric codes are those of [RFC 5424](https://www.rfc-editor.org/rfc/rfc5424#section-6.2.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Facility {
+    /// kernel messages
+    Kern,
+    /// user-level messages
+    #[default]
+    User,
+    /// mail system
+    Mail,
+    /// system daemons
+    Daemon,
+    /// security/authorization messages
+    Auth,
+    /// messages generated internally by the syslog daemon
+    Syslog,
+    /// line printer subsystem
+    Lpr,
+    /// network news subsystem
+    News,
+    /// UUCP subsystem
+    Uucp,
+    /// clock daemon
+    Cron,
+    /// private security/authorization messages
+    Authpriv,
+    /// FTP daemon
+    Ftp,
+    /// local use 0
+    Local0,
+    /// local use 1
+    Local1,
+    /// local use 2
+    Local2,
+    /// local use 3
+    Local3,
+    /// local use 4
+    Local4,
+    /// local use 5
+    Local5,
+    /// local use 6
+    Local6,
+    /// local use 7
+    Local7,
+}
+
+impl Facility {
+    /// the numeric code of the facility
+    #[must_use]
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::Kern => 0,
+            Self::User => 1,
+            Self::Mail => 2,
+            Self::Daemon => 3,
+            Self::Auth => 4,
+            Self::Syslog => 5,
+            Self::Lpr => 6,
+            Self::News => 7,
+            Self::Uucp => 8,
+            Self::Cron => 9,
+            Self::Authpriv => 10,
+            Self::Ftp => 11,
+            Self::Local0 => 16,
+            Self::Local1 => 17,
+            Self::Local2 => 18,
+            Self::Local3 => 19,
+            Self::Local4 => 20,
+            Self::Local5 => 21,
+            Self::Local6 => 22,
+            Self::Local7 => 23,
+        }
+    }
+}
+
+/// The syslog severity of events at `level`
+///
+/// | [Level]               | severity          |
+/// |-----------------------|-------------------|
+/// | [ERROR](Level::ERROR) | 3 (error)         |
+/// | [WARN](Level::WARN)   | 4 (warning)       |
+/// | [INFO](Level::INFO)   | 6 (informational) |
+/// | [DEBUG](Level::DEBUG) | 7 (debug)         |
+/// | [TRACE](Level::TRACE) | 7 (debug)         |
+///
+/// Syslog has no level below debug, so traces are sent as debug messages.
+#[must_use]
+pub const fn severity(level: Level) -> u8 {
+    match level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        Level::DEBUG | Level::TRACE => 7,
+    }
+}
+
+#[cfg(unix)]
+/// The settings of the syslog sink of a [`LoggerBuilder`](crate::LoggerBuilder)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Syslog {
+    /// send events to the syslog daemon
+    pub(crate) enabled: bool,
+    /// the socket of the syslog daemon
+    pub(crate) path: PathBuf,
+    /// the format of the messages
+    pub(crate) format: SyslogFormat,
+    /// the facility of the messages
+    pub(crate) facility: Facility,
+    /// the name of the program in the messages, the crate name if [None]
+    pub(crate) app_name: Option<String>,
+    /// the maximum verbosity level, that of the terminal if [None]
+    pub(crate) level: Option<LevelFilter>,
+}
+
+#[cfg(unix)]
+impl Default for Syslog {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from(DEFAULT_SYSLOG_PATH),
+            format: SyslogFormat::default(),
+            facility: Facility::default(),
+            app_name: None,
+            level: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Syslog {
+    /// connect to the syslog daemon
+    ///
+    /// Fails if nothing listens at [`Self::path`], so that a wrong path is noticed when the
+    /// [Logger](crate::Logger) is built and not when the first event is silently lost.
+    pub(crate) fn connect(&self) -> io::Result<SyslogSink> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(&self.path)?;
+        let app_name = self
+            .app_name
+            .clone()
+            .unwrap_or_else(|| libpt_core::get_crate_name().unwrap_or_else(|| "libpt".to_string()));
+        Ok(SyslogSink {
+            inner: Arc::new(SinkInner {
+                socket,
+                path: self.path.clone(),
+                format: self.format,
+                facility: self.facility,
+                hostname: gethostname::gethostname().to_string_lossy().into_owned(),
+                app_name,
+                pid: std::process::id(),
+            }),
+        })
+    }
+}
+
+#[cfg(unix)]
+/// Sends each formatted event as one datagram to the syslog daemon
+#[derive(Debug, Clone)]
+pub struct SyslogSink {
+    /// shared between all clones of the sink
+    inner: Arc<SinkInner>,
+}
+
+#[cfg(unix)]
+/// The connection and the header fields that are the same for all messages
+#[derive(Debug)]
+struct SinkInner {
+    /// the socket, connected to [`Self::path`]
+    socket: UnixDatagram,
+    /// the socket of the syslog daemon, to reconnect after it was restarted
+    path: PathBuf,
+    /// the format of the messages
+    format: SyslogFormat,
+    /// the facility of the messages
+    facility: Facility,
+    /// the name of this machine
+    hostname: String,
+    /// the name of the program
+    app_name: String,
+    /// the id of this process
+    pid: u32,
+}
+
+#[cfg(unix)]
+impl SinkInner {
+    /// put the syslog header in front of the formatted event `msg`
+    fn message(&self, level: Level, msg: &[u8]) -> Vec<u8> {
+        let priority = self.facility.code() * 8 + severity(level);
+        let now = Local::now();
+        let header = match self.format {
+            SyslogFormat::Rfc5424 => format!(
+                "<{priority}>1 {} {} {} {} - - ",
+                now.to_rfc3339_opts(SecondsFormat::Micros, false),
+                self.hostname,
+                self.app_name,
+                self.pid
+            ),
+            SyslogFormat::Rfc3164 => format!(
+                "<{priority}>{} {} {}[{}]: ",
+                now.format("%b %e %H:%M:%S"),
+                self.hostname,
+                self.app_name,
+                self.pid
+            ),
+        };
+        let mut message = header.into_bytes();
+        // the formatter ends each event with a newline, which syslog does not want
+        message.extend_from_slice(msg.strip_suffix(b"\n").unwrap_or(msg));
+        message
+    }
+
+    /// send one message, reconnecting once if the syslog daemon was restarted
+    fn send(&self, message: &[u8]) -> io::Result<()> {
+        if self.socket.send(message).is_err() {
+            self.socket.connect(&self.path)?;
+            self.socket.send(message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl<'a> MakeWriter<'a> for SyslogSink {
+    type Writer = SyslogWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        SyslogWriter {
+            sink: &self.inner,
+            level: Level::INFO,
+            buf: Vec::new(),
+        }
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        SyslogWriter {
+            sink: &self.inner,
+            level: *meta.level(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+#[cfg(unix)]
+/// Collects one formatted event and sends it to the syslog daemon when dropped
+#[derive(Debug)]
+pub struct SyslogWriter<'a> {
+    /// where the event goes
+    sink: &'a SinkInner,
+    /// the level of the event, for its severity
+    level: Level,
+    /// the event so far
+    buf: Vec<u8>,
+}
+
+#[cfg(unix)]
+impl Write for SyslogWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SyslogWriter<'_> {
+    fn drop(&mut self) {
+        if !self.buf.is_empty() {
+            // there is nowhere to report a failure to log
+            let _ = self.sink.send(&self.sink.message(self.level, &self.buf));
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! # Tree output for [`pt-log`](crate)
+//!
+//! This module draws the nesting of spans with box characters, for [`LogFormat::Tree`]. Each
+//! span gets a line with its fields when it is first entered and a line with the elapsed time
+//! when it is closed. Events are indented by the spans they happen in:
+//!
+//! ```text
+//! ┌─ download url="https://example.com"
+//! │  INFO  connecting
+//! │  ┌─ retry attempt=2
+//! │  │  WARN  timed out
+//! │  └─ retry 1.02s
+//! └─ download 1.53s
+//! ```
+//!
+//! [`LogFormat::Tree`]: crate::LogFormat::Tree
+
+use std::{
+    fmt::{self, Write as _},
+    io::Write as _,
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+use nu_ansi_term::{Color, Style};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Level, Metadata, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::{format::Writer, time::FormatTime, MakeWriter},
+    layer::Context,
+    registry::LookupSpan,
+    Layer,
+};
+
+/// What is drawn before the lines inside of a span
+const INDENT: &str = "│  ";
+/// What is drawn before the name of a span when it is entered
+const OPEN: &str = "┌─ ";
+/// What is drawn before the name of a span when it is closed
+const CLOSE: &str = "└─ ";
+
+/// A layer that writes events and spans as an indented tree
+///
+/// `K` marks the state this layer keeps for each span, so that several sinks can draw trees, see
+/// [`SinkFields`](crate::fields::SinkFields).
+#[derive(Debug)]
+pub struct TreeLayer<K, W, T> {
+    /// where the lines are written
+    writer: W,
+    /// how the time at the start of each line is written
+    timer: T,
+    /// use ANSI control sequences
+    ansi: bool,
+    /// show the level of events
+    display_level: bool,
+    /// show the target of events
+    display_target: bool,
+    /// the sink, only used for the type
+    sink: PhantomData<fn() -> K>,
+}
+
+/// The state of a span for the sink `K`, kept in its extensions
+#[derive(Debug)]
+struct TreeSpan<K> {
+    /// the formatted fields of the span
+    fields: String,
+    /// when the span was first entered by this sink, [None] before that
+    entered: Option<Instant>,
+    /// the sink, only used for the type
+    sink: PhantomData<fn() -> K>,
+}
+
+impl<K, W, T> TreeLayer<K, W, T>
+where
+    W: for<'writer> MakeWriter<'writer> + 'static,
+    T: FormatTime,
+{
+    /// create a layer writing to `writer`, with timestamps from `timer`
+    pub const fn new(
+        writer: W,
+        timer: T,
+        ansi: bool,
+        display_level: bool,
+        display_target: bool,
+    ) -> Self {
+        Self {
+            writer,
+            timer,
+            ansi,
+            display_level,
+            display_target,
+            sink: PhantomData,
+        }
+    }
+
+    /// start a line with the time and the lines of `depth` enclosing spans
+    fn start_line(&self, line: &mut String, depth: usize) {
+        let mut time = String::new();
+        if self.timer.format_time(&mut Writer::new(&mut time)).is_ok() && !time.is_empty() {
+            let _ = write!(line, "{} ", self.paint(Style::new().dimmed(), &time));
+        }
+        let indent = INDENT.repeat(depth);
+        let _ = write!(line, "{}", self.paint(Style::new().dimmed(), &indent));
+    }
+
+    /// the name of a span, with its box character
+    fn span_line(&self, depth: usize, corner: &str, name: &str, rest: &str) -> String {
+        let mut line = String::new();
+        self.start_line(&mut line, depth);
+        let _ = write!(
+            line,
+            "{}{}",
+            self.paint(Style::new().dimmed(), corner),
+            self.paint(Style::new().bold(), name)
+        );
+        if !rest.is_empty() {
+            let _ = write!(line, " {rest}");
+        }
+        line
+    }
+
+    /// apply `style` if ANSI control sequences are wanted
+    fn paint(&self, style: Style, text: &str) -> String {
+        if self.ansi {
+            style.paint(text).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// write one finished line for the span or event described by `meta`
+    fn write_line(&self, meta: &Metadata<'_>, mut line: String) {
+        line.push('\n');
+        // there is nowhere to report a failure to log
+        let _ = self.writer.make_writer_for(meta).write_all(line.as_bytes());
+    }
+}
+
+impl<S, K, W, T> Layer<S> for TreeLayer<K, W, T>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    K: 'static,
+    W: for<'writer> MakeWriter<'writer> + 'static,
+    T: FormatTime + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = String::new();
+        attrs.record(&mut FieldVisitor::new(&mut fields));
+        span.extensions_mut().insert(TreeSpan::<K> {
+            fields,
+            entered: None,
+            sink: PhantomData,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(tree) = extensions.get_mut::<TreeSpan<K>>() {
+            values.record(&mut FieldVisitor::new(&mut tree.fields));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        let Some(tree) = extensions.get_mut::<TreeSpan<K>>() else {
+            return;
+        };
+        // a span can be entered many times, but its fields are shown only once
+        if tree.entered.is_some() {
+            return;
+        }
+        tree.entered = Some(Instant::now());
+        let depth = span.scope().skip(1).count();
+        let line = self.span_line(depth, OPEN, span.name(), &tree.fields);
+        drop(extensions);
+        self.write_line(span.metadata(), line);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let depth = ctx.event_scope(event).map_or(0, Iterator::count);
+        let meta = event.metadata();
+        let mut line = String::new();
+        self.start_line(&mut line, depth);
+        if self.display_level {
+            let style = match *meta.level() {
+                Level::ERROR => Color::Red.normal(),
+                Level::WARN => Color::Yellow.normal(),
+                Level::INFO => Color::Green.normal(),
+                Level::DEBUG => Color::Blue.normal(),
+                Level::TRACE => Color::Purple.normal(),
+            };
+            let _ = write!(
+                line,
+                "{} ",
+                self.paint(style, &format!("{:<5}", meta.level()))
+            );
+        }
+        if self.display_target {
+            let _ = write!(
+                line,
+                "{} ",
+                self.paint(Style::new().dimmed(), &format!("{}:", meta.target()))
+            );
+        }
+        event.record(&mut FieldVisitor::new(&mut line));
+        self.write_line(meta, line);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(entered) = span
+            .extensions()
+            .get::<TreeSpan<K>>()
+            .and_then(|tree| tree.entered)
+        else {
+            return;
+        };
+        let depth = span.scope().skip(1).count();
+        let elapsed = self.paint(Style::new().dimmed(), &format_elapsed(entered.elapsed()));
+        let line = self.span_line(depth, CLOSE, span.name(), &elapsed);
+        self.write_line(span.metadata(), line);
+    }
+}
+
+/// Writes the message of an event as is and all other fields as `key=value`, separated by spaces
+struct FieldVisitor<'a> {
+    /// where the fields are written
+    out: &'a mut String,
+}
+
+impl<'a> FieldVisitor<'a> {
+    /// append fields to `out`
+    const fn new(out: &'a mut String) -> Self {
+        Self { out }
+    }
+
+    /// separate the next field from what is already written
+    fn separate(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+    }
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.separate();
+        let _ = if field.name() == "message" {
+            write!(self.out, "{value:?}")
+        } else {
+            write!(self.out, "{}={value:?}", field.name())
+        };
+    }
+}
+
+/// format the time a span was open, with two decimals and a fitting unit
+fn format_elapsed(elapsed: Duration) -> String {
+    #[allow(clippy::cast_precision_loss)] // spans that take centuries are not shown exactly
+    let nanos = elapsed.as_nanos() as f64;
+    if nanos >= 1e9 {
+        format!("{:.2}s", nanos / 1e9)
+    } else if nanos >= 1e6 {
+        format!("{:.2}ms", nanos / 1e6)
+    } else if nanos >= 1e3 {
+        format!("{:.2}µs", nanos / 1e3)
+    } else {
+        format!("{nanos}ns")
+    }
+}
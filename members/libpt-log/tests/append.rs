@@ -0,0 +1,24 @@
+use libpt_log::{info, Logger};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn append_with_template() {
+    let dir = TestDir::new("append");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("pt_{}.log", std::process::id()));
+    std::fs::write(&path, "evidence of an earlier run\n").unwrap();
+
+    let _logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .logfile_template("pt_{pid}.log")
+        .build()
+        .unwrap();
+    info!("second run");
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.starts_with("evidence of an earlier run\n"));
+    assert!(content.contains("second run"));
+}
@@ -0,0 +1,57 @@
+use libpt_log::{capture::Capture, debug, error, info, trace, Level};
+
+#[test]
+fn records_structured_events() {
+    let capture = Capture::start();
+    info!(target: "app::db", rows = 3, table = "users", "query done");
+
+    let events = capture.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].level, Level::INFO);
+    assert_eq!(events[0].target, "app::db");
+    assert_eq!(events[0].message, "query done");
+    assert_eq!(events[0].fields["rows"], "3");
+    assert_eq!(events[0].fields["table"], "users");
+}
+
+#[test]
+fn respects_level() {
+    let capture = Capture::start_with_level(Level::DEBUG);
+    debug!("kept");
+    trace!("dropped");
+
+    assert!(capture.contains(Level::DEBUG, "kept"));
+    assert!(!capture.contains(Level::TRACE, "dropped"));
+    capture.clear();
+    assert!(capture.events().is_empty());
+}
+
+#[test]
+fn isolated_between_threads() {
+    let capture = Capture::start();
+    let other = std::thread::spawn(|| {
+        let capture = Capture::start();
+        error!("from the other thread");
+        capture.events()
+    })
+    .join()
+    .unwrap();
+    error!("from this thread");
+
+    assert_eq!(other.len(), 1);
+    assert_eq!(other[0].message, "from the other thread");
+    let events = capture.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].message, "from this thread");
+}
+
+#[test]
+fn scoped() {
+    {
+        let _capture = Capture::start();
+        info!("inside");
+    }
+    let capture = Capture::start();
+    info!("outside");
+    assert_eq!(capture.events().len(), 1);
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "chrome")]
+use libpt_log::{info, tracing::info_span, Logger};
+
+#[test]
+fn spans_as_trace_events() {
+    let path = std::env::temp_dir().join(format!("libpt-log-chrome-{}.json", std::process::id()));
+    {
+        let _logger = Logger::builder()
+            .chrome_trace(path.clone())
+            .build_scoped()
+            .unwrap();
+        info_span!("outer", job = 7).in_scope(|| {
+            info_span!("inner").in_scope(|| info!("working"));
+        });
+    }
+
+    let trace = std::fs::read_to_string(&path).unwrap();
+    assert!(trace.starts_with('['), "{trace}");
+    assert!(trace.trim_end().ends_with(']'), "{trace}");
+    assert!(trace.contains(r#""ph":"B""#), "{trace}");
+    assert!(trace.contains(r#""ph":"E""#), "{trace}");
+    assert!(trace.contains(r#""name":"outer""#), "{trace}");
+    assert!(trace.contains(r#""name":"inner""#), "{trace}");
+    assert!(trace.contains(r#""job":"7""#), "{trace}");
+    assert!(trace.contains("working"), "{trace}");
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,64 @@
+//! # Helpers shared by the tests of `libpt-log`
+#![allow(dead_code)] // every test file uses only some of the helpers
+
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+/// A directory for the files of one test, deleted when dropped, even if the test fails
+#[derive(Debug)]
+pub struct TestDir(PathBuf);
+
+impl TestDir {
+    /// `libpt-log-{name}-{pid}` in the temporary directory, with leftovers of an earlier run
+    /// removed
+    ///
+    /// The directory itself is not created, the [Logger](libpt_log::Logger) does that.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("libpt-log-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        Self(path)
+    }
+
+    /// the path of the directory, for [`LoggerBuilder::log_dir`](libpt_log::LoggerBuilder::log_dir)
+    pub fn path(&self) -> PathBuf {
+        self.0.clone()
+    }
+
+    /// the content of the only logfile in the directory
+    pub fn logfile(&self) -> String {
+        let logfile = std::fs::read_dir(&self.0).unwrap().next().unwrap().unwrap();
+        std::fs::read_to_string(logfile.path()).unwrap()
+    }
+
+    /// the names of all files in the directory, sorted
+    pub fn files(&self) -> Vec<String> {
+        let mut files: Vec<_> = std::fs::read_dir(&self.0)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "gzip")]
+use std::io::Read;
+
+use libpt_log::{info, Compression, Logger, Rotation};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn rotated_logfiles_are_compressed() {
+    let dir = TestDir::new("compression");
+    std::fs::create_dir_all(&dir).unwrap();
+    // an old logfile from before compression was enabled
+    std::fs::write(dir.join("app.log.1"), "old\n").unwrap();
+
+    let logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .logfile_template("app.log")
+        .ansi(false)
+        .rotation(Rotation::Size {
+            max_bytes: 256,
+            keep: 3,
+        })
+        .compression(Compression::Gzip)
+        .max_logfiles(3)
+        .build()
+        .unwrap();
+
+    for i in 0..64 {
+        info!("this is message number {i}");
+    }
+    logger.flush().unwrap();
+
+    let files = dir.files();
+    assert_eq!(files, ["app.log", "app.log.1.gz", "app.log.2.gz"]);
+
+    let mut decoder =
+        flate2::read::GzDecoder::new(std::fs::File::open(dir.join("app.log.1.gz")).unwrap());
+    let mut content = String::new();
+    decoder.read_to_string(&mut content).unwrap();
+    assert!(content.contains("this is message number"));
+}
+
+#[test]
+fn compression_needs_rotation() {
+    let dir = TestDir::new("compression-never");
+    for rotation in [
+        Rotation::Never,
+        Rotation::Size {
+            max_bytes: 256,
+            keep: 0,
+        },
+    ] {
+        let result = Logger::builder()
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .rotation(rotation)
+            .compression(Compression::Gzip)
+            .build_scoped();
+        assert!(result.is_err(), "{rotation:?}");
+    }
+    assert!(!dir.exists());
+}
+
+#[test]
+fn logfiles_of_earlier_runs_are_compressed() {
+    let dir = TestDir::new("compression-earlier");
+    std::fs::create_dir_all(&dir).unwrap();
+    // finished by an earlier run
+    let old = std::fs::File::create(dir.join("app_2000-01-01_1.log")).unwrap();
+    old.set_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(946_771_200))
+        .unwrap();
+    drop(old);
+    // another instance that is still running writes into this one
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let running = format!("app_{today}_1.log");
+    std::fs::write(dir.join(&running), "still running\n").unwrap();
+
+    let logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .logfile_template("app_{date}_{pid}.log")
+        .rotation(Rotation::Daily)
+        .compression(Compression::Gzip)
+        .build_scoped()
+        .unwrap();
+    logger.flush().unwrap();
+    drop(logger);
+
+    let files = dir.files();
+    let mut expected = vec![
+        "app_2000-01-01_1.log.gz".to_string(),
+        running,
+        format!("app_{today}_{}.log", std::process::id()),
+    ];
+    expected.sort();
+    assert_eq!(files, expected);
+}
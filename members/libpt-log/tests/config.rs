@@ -0,0 +1,73 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{
+    config::LoggerConfig, debug, info, LevelFilter, LogFormat, Logger, LoggerBuilder, Rotation,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct AppConfig {
+    #[allow(dead_code)]
+    name: String,
+    logging: LoggerConfig,
+}
+
+const APP_CONFIG: &str = r#"
+name = "myapp"
+
+[logging]
+level = "debug"
+filter = "noisy=warn"
+display_target = true
+ansi = false
+display_time = false
+
+[logging.file]
+level = "trace"
+format = "json"
+rotation = { size = { max_bytes = 1000, keep = 3 } }
+max_age_secs = 3600
+"#;
+
+#[test]
+fn logging_section() {
+    let config: AppConfig = toml::from_str(APP_CONFIG).unwrap();
+    let logging = &config.logging;
+    assert_eq!(logging.level, Some(LevelFilter::DEBUG));
+    assert_eq!(logging.file.level, Some(LevelFilter::TRACE));
+    assert_eq!(logging.file.format, Some(LogFormat::Json));
+    assert_eq!(
+        logging.file.rotation,
+        Some(Rotation::Size {
+            max_bytes: 1000,
+            keep: 3
+        })
+    );
+
+    let out = get_stdout_for!({
+        let _logger = LoggerBuilder::from_config(logging).build_scoped().unwrap();
+        debug!("from the config");
+        info!(target: "noisy", "filtered");
+    });
+    assert_eq!(out, "DEBUG config: from the config\n");
+}
+
+#[test]
+fn defaults_are_kept() {
+    let config: LoggerConfig = toml::from_str("display_level = false").unwrap();
+    assert_eq!(
+        Logger::builder()
+            .display_time(false)
+            .ansi(false)
+            .apply_config(&config),
+        Logger::builder()
+            .display_time(false)
+            .ansi(false)
+            .display_level(false)
+    );
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    assert!(toml::from_str::<LoggerConfig>("levle = \"info\"").is_err());
+    assert!(toml::from_str::<LoggerConfig>("level = \"loud\"").is_err());
+}
@@ -0,0 +1,15 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{info, Logger};
+
+#[test]
+fn no_time_no_level() {
+    let _logger = Logger::builder()
+        .ansi(false)
+        .display_time(false)
+        .display_level(false)
+        .build()
+        .unwrap();
+
+    let out = get_stdout_for!(info!("hello world"));
+    assert_eq!(out, "hello world\n");
+}
@@ -0,0 +1,29 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{debug, info, trace, warn, Level, Logger};
+
+#[test]
+fn directives() {
+    std::env::set_var("LIBPT_LOG_TEST_FILTER", "loud=trace,not a directive");
+    let _logger = Logger::builder()
+        .ansi(false)
+        .display_time(false)
+        .display_target(true)
+        .set_level(Level::DEBUG)
+        .filter("noisy=warn,silent=off")
+        .filter_env("LIBPT_LOG_TEST_FILTER")
+        .build()
+        .unwrap();
+
+    let out = get_stdout_for!({
+        debug!(target: "mine", "shown");
+        trace!(target: "mine", "hidden");
+        info!(target: "noisy", "hidden");
+        warn!(target: "noisy", "shown");
+        warn!(target: "silent", "hidden");
+        trace!(target: "loud", "shown");
+    });
+    assert_eq!(
+        out,
+        "DEBUG mine: shown\n WARN noisy: shown\nTRACE loud: shown\n",
+    );
+}
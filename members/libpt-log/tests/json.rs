@@ -0,0 +1,37 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{info, tracing::info_span, LogFormat, Logger};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn json_terminal_text_file() {
+    let dir = TestDir::new("json");
+    let _logger = Logger::builder()
+        .terminal_format(LogFormat::Json)
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .ansi(false)
+        .build()
+        .unwrap();
+
+    let out = get_stdout_for!({
+        let span = info_span!("request", id = 7);
+        let _entered = span.enter();
+        info!(answer = 42, "hello json");
+    });
+    let line = out.lines().next().unwrap();
+    assert!(line.starts_with('{') && line.ends_with('}'), "{line}");
+    assert!(line.contains(r#""timestamp":"#));
+    assert!(line.contains(r#""level":"INFO""#));
+    assert!(line.contains(r#""message":"hello json""#));
+    assert!(line.contains(r#""answer":42"#));
+    assert!(line.contains(r#""target":"json""#));
+    assert!(line.contains(r#""spans":[{"id":7,"name":"request"}]"#));
+
+    let content = dir.logfile();
+    assert!(content.contains("INFO"));
+    assert!(content.contains("hello json answer=42"));
+    assert!(content.contains("request{id=7}"));
+    assert!(!content.contains(r#""level""#));
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "log")]
+use libpt_core::get_stdout_for;
+use libpt_log::{Level, Logger};
+
+#[test]
+fn log_records() {
+    let logger = Logger::builder()
+        .ansi(false)
+        .display_time(false)
+        .display_target(true)
+        .log_bridge(true)
+        .build()
+        .unwrap();
+
+    let out = get_stdout_for!({
+        log::info!(target: "dependency", "from the log crate");
+        log::debug!("hidden");
+    });
+    assert_eq!(out, " INFO dependency: from the log crate\n");
+
+    logger.set_level(Level::DEBUG).unwrap();
+    let out = get_stdout_for!({
+        log::debug!("shown after the change");
+    });
+    assert_eq!(out, "DEBUG log_bridge: shown after the change\n");
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "log")]
+use libpt_log::Logger;
+
+struct Other;
+
+impl log::Log for Other {
+    fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+        false
+    }
+
+    fn log(&self, _: &log::Record<'_>) {}
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn other_log_logger() {
+    log::set_logger(&Other).unwrap();
+    assert!(Logger::builder().log_bridge(true).build().is_err());
+    // nothing was installed by the failed attempt
+    Logger::builder().build().unwrap();
+}
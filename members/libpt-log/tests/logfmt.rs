@@ -0,0 +1,88 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{
+    info, tracing::info_span, warn, Level, LevelFilter, LogFormat, Logger, TerminalStream,
+};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn escaping_and_spans() {
+    let logger = Logger::builder()
+        .terminal_format(LogFormat::Logfmt)
+        .display_time(false)
+        .build_scoped()
+        .unwrap();
+
+    let stdout = get_stdout_for!({
+        info!(bytes = 42, path = "/tmp/a b", empty = "", "plain");
+        warn!(
+            quote = "say \"hi\"\\",
+            newline = "a\nb",
+            equals = "a=b",
+            "with spaces"
+        );
+        info_span!("main", run = 1).in_scope(|| {
+            info_span!("download", url = "https://example.com")
+                .in_scope(|| info!(ok = true, "got it"));
+        });
+    });
+    drop(logger);
+    assert_eq!(
+        stdout,
+        concat!(
+            "level=info target=logfmt msg=plain bytes=42 path=\"/tmp/a b\" empty=\"\"\n",
+            "level=warn target=logfmt msg=\"with spaces\" quote=\"say \\\"hi\\\"\\\\\" ",
+            "newline=\"a\\nb\" equals=\"a=b\"\n",
+            "level=info target=logfmt span=main:download run=1 url=https://example.com ",
+            "msg=\"got it\" ok=true\n",
+        )
+    );
+}
+
+#[test]
+fn timestamp_in_logfile() {
+    let dir = TestDir::new("logfmt");
+    {
+        // keep the stdout clean for the other test
+        let _logger = Logger::builder()
+            .set_level(LevelFilter::OFF)
+            .file_level(Level::INFO)
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .file_format(LogFormat::Logfmt)
+            .build_scoped()
+            .unwrap();
+        info!("hello");
+    }
+
+    let log = dir.logfile();
+    assert!(log.starts_with("ts=20"), "{log}");
+    assert!(
+        log.ends_with("Z level=info target=logfmt msg=hello\n"),
+        "{log}"
+    );
+}
+
+#[test]
+fn recorded_fields_on_two_sinks() {
+    let dir = TestDir::new("logfmt-two");
+    {
+        // stdout is captured by another test
+        let _logger = Logger::builder()
+            .terminal_format(LogFormat::Logfmt)
+            .terminal_stream(TerminalStream::Stderr)
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .file_format(LogFormat::Logfmt)
+            .display_time(false)
+            .build_scoped()
+            .unwrap();
+        let span = info_span!("a", x = libpt_log::tracing::field::Empty);
+        span.record("x", 1);
+        span.in_scope(|| info!("y"));
+    }
+
+    let log = dir.logfile();
+    assert_eq!(log, "level=info target=logfmt span=a x=1 msg=y\n");
+}
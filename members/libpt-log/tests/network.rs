@@ -0,0 +1,141 @@
+use std::{
+    io::Read,
+    net::{TcpListener, UdpSocket},
+    time::Duration,
+};
+
+use libpt_log::{error, info, Framing, Level, LogFormat, Logger, Protocol};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn tcp_json() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    {
+        let logger = Logger::builder()
+            .log_to_network(true)
+            .network_address(address)
+            .build_scoped()
+            .unwrap();
+        info!(answer = 42, "to the collector");
+        error!("it broke");
+        logger.flush().unwrap();
+    }
+
+    // the connection is closed once the logger is gone
+    let (mut stream, _) = listener.accept().unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut received = String::new();
+    stream.read_to_string(&mut received).unwrap();
+    let lines: Vec<&str> = received.lines().collect();
+    assert_eq!(lines.len(), 2, "{received:?}");
+    assert!(lines[0].starts_with(r#"{"timestamp":""#), "{received:?}");
+    assert!(
+        lines[0].ends_with(
+            r#""level":"INFO","message":"to the collector","answer":42,"target":"network"}"#
+        ),
+        "{received:?}"
+    );
+    assert!(lines[1].contains(r#""message":"it broke""#), "{received:?}");
+}
+
+#[test]
+fn udp_gelf() {
+    let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+    collector
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let address = collector.local_addr().unwrap().to_string();
+    {
+        let logger = Logger::builder()
+            .log_to_network(true)
+            .network_address(address)
+            .network_protocol(Protocol::Udp)
+            .network_framing(Framing::Gelf)
+            .network_level(Level::WARN)
+            .build_scoped()
+            .unwrap();
+        info!("below the network level");
+        error!(answer = 42, flag = true, "it broke");
+        logger.flush().unwrap();
+    }
+
+    let mut buf = vec![0; 8192];
+    let len = collector.recv(&mut buf).unwrap();
+    let message = String::from_utf8(buf[..len].to_vec()).unwrap();
+    assert!(message.contains(r#""version":"1.1""#), "{message:?}");
+    assert!(message.contains(r#""host":""#), "{message:?}");
+    assert!(
+        message.contains(r#""short_message":"it broke""#),
+        "{message:?}"
+    );
+    assert!(message.contains(r#""timestamp":"#), "{message:?}");
+    // syslog error severity
+    assert!(message.contains(r#""level":3"#), "{message:?}");
+    assert!(message.contains(r#""_answer":42"#), "{message:?}");
+    assert!(message.contains(r#""_flag":"true""#), "{message:?}");
+    assert!(message.contains(r#""_target":"network""#), "{message:?}");
+}
+
+#[test]
+fn fallback_to_logfile() {
+    // nothing listens on this port once the listener is gone
+    let address = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    };
+    let dir = TestDir::new("network-fallback");
+    {
+        let logger = Logger::builder()
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .file_level(Level::ERROR)
+            .file_format(LogFormat::Json)
+            .log_to_network(true)
+            .network_address(address)
+            .network_fallback_after(Duration::ZERO)
+            .build_scoped()
+            .unwrap();
+        info!("missed by the collector");
+        logger.flush().unwrap();
+    }
+
+    let log = dir.logfile();
+    assert_eq!(log.lines().count(), 1, "{log:?}");
+    assert!(
+        log.contains(r#""message":"missed by the collector""#),
+        "{log:?}"
+    );
+}
+
+#[test]
+fn fallback_format_differs() {
+    let address = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    };
+    let dir = TestDir::new("network-format");
+    {
+        let logger = Logger::builder()
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .file_level(Level::ERROR)
+            .log_to_network(true)
+            .network_address(address)
+            .network_fallback_after(Duration::ZERO)
+            .build_scoped()
+            .unwrap();
+        logger.flush().unwrap();
+    }
+
+    // the warning itself is missed by the collector as well
+    let log = dir.logfile();
+    assert!(
+        log.contains("written to the logfile as json, not as text"),
+        "{log:?}"
+    );
+}
@@ -0,0 +1,33 @@
+use libpt_log::{info, Logger};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn non_blocking() {
+    let dir = TestDir::new("nb");
+    let logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .non_blocking(true)
+        .build()
+        .unwrap();
+
+    let threads: Vec<_> = (0..4)
+        .map(|t| {
+            std::thread::spawn(move || {
+                for i in 0..250 {
+                    info!("thread {t} message {i}");
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    logger.flush().unwrap();
+
+    let content = dir.logfile();
+    assert_eq!(content.lines().count(), 1000);
+    assert!(content.contains("thread 3 message 249"));
+}
@@ -0,0 +1,30 @@
+use libpt_log::Logger;
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn panic_is_logged() {
+    let dir = TestDir::new("panic");
+    let _logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .non_blocking(true)
+        .panic_hook(true)
+        .build()
+        .unwrap();
+
+    let result = std::thread::Builder::new()
+        .name("doomed".to_string())
+        .spawn(|| panic!("boom"))
+        .unwrap()
+        .join();
+    assert!(result.is_err());
+
+    let content = dir.logfile();
+    assert!(content.contains("ERROR"), "{content}");
+    assert!(content.contains("panicked: boom"));
+    assert!(content.contains("thread=\"doomed\""));
+    assert!(content.contains("tests/panic.rs:"));
+    assert!(content.contains("backtrace="));
+}
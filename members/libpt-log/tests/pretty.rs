@@ -0,0 +1,22 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{info, Logger};
+
+#[test]
+fn pretty() {
+    let _logger = Logger::builder()
+        .ansi(false)
+        .display_time(false)
+        .display_line_number(true)
+        .display_filename(true)
+        .pretty(true)
+        .build()
+        .unwrap();
+
+    let out = get_stdout_for!(info!(answer = 42, "hello world"));
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines.len() > 1, "{out}");
+    assert!(lines[0].contains("INFO"));
+    assert!(lines[0].contains("hello world"));
+    assert!(out.contains("answer: 42"));
+    assert!(out.contains("at members/libpt-log/tests/pretty.rs"));
+}
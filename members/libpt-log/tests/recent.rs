@@ -0,0 +1,73 @@
+use libpt_log::{debug, info, trace, tracing::info_span, Level, LevelFilter, LogFormat, Logger};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn dump_recent_to_logfile() {
+    let dir = TestDir::new("recent");
+    let logger = Logger::builder()
+        .set_level(Level::INFO)
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .recent_events(2)
+        .recent_level(Level::DEBUG)
+        .build()
+        .unwrap();
+
+    debug!("forgotten");
+    info!("working");
+    trace!("too verbose");
+    debug!("about to fail");
+    logger.dump_recent().unwrap();
+    // the buffer is empty after a dump
+    logger.dump_recent().unwrap();
+
+    let content = dir.logfile();
+    let dump = content
+        .split_once("---- 2 most recent events ----\n")
+        .unwrap()
+        .1;
+    let (dump, rest) = dump.split_once("---- 0 most recent events ----\n").unwrap();
+    assert!(rest.is_empty());
+    let lines: Vec<_> = dump.lines().collect();
+    assert_eq!(lines.len(), 2, "{dump}");
+    assert!(lines[0].ends_with(" INFO working"));
+    assert!(lines[1].ends_with("DEBUG about to fail"));
+    assert!(!content.contains("forgotten"));
+    assert!(!content.contains("too verbose"));
+}
+
+#[test]
+fn dump_is_text() {
+    let dir = TestDir::new("recent-text");
+    for format in [LogFormat::Logfmt, LogFormat::Tree] {
+        let _ = std::fs::remove_dir_all(&dir);
+        let logger = Logger::builder()
+            .set_level(LevelFilter::OFF)
+            .file_level(Level::INFO)
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .file_format(format)
+            .display_time(false)
+            .recent_events(1)
+            .build_scoped()
+            .unwrap();
+
+        let span = info_span!("a", x = libpt_log::tracing::field::Empty);
+        span.record("x", 1);
+        span.in_scope(|| info!("y"));
+        logger.dump_recent().unwrap();
+
+        let content = dir.logfile();
+        let dump = content
+            .split_once("---- 1 most recent events ----\n")
+            .unwrap()
+            .1;
+        assert_eq!(dump, " INFO a{x=1}: y\n", "{format:?}");
+        if format == LogFormat::Logfmt {
+            assert!(content.starts_with("level=info target=recent span=a x=1 msg=y\n"));
+        }
+        drop(logger);
+    }
+}
@@ -0,0 +1,53 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{debug, info, warn, Level, LevelFilter, Logger};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn reload() {
+    let dir = TestDir::new("reload");
+    let logger = Logger::builder()
+        .ansi(false)
+        .display_time(false)
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .build()
+        .unwrap();
+
+    let out = get_stdout_for!({
+        debug!("hidden before");
+        logger.set_level(Level::DEBUG).unwrap();
+        debug!("shown after");
+        logger.set_filter("reload=warn").unwrap();
+        info!("hidden by directive");
+        warn!("shown by directive");
+    });
+    assert_eq!(out, "DEBUG shown after\n WARN shown by directive\n");
+    assert!(logger.set_filter("reload=nonsense").is_err());
+
+    assert_eq!(dir.logfile(), out);
+}
+
+#[test]
+fn own_level_is_kept() {
+    let dir = TestDir::new("reload-own");
+    // the terminal stays silent, stdout is captured by the other test
+    let logger = Logger::builder()
+        .set_level(LevelFilter::OFF)
+        .ansi(false)
+        .display_time(false)
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .file_level(Level::DEBUG)
+        .build_scoped()
+        .unwrap();
+
+    logger.set_level(Level::ERROR).unwrap();
+    debug!("still in the logfile");
+    logger.flush().unwrap();
+
+    assert_eq!(dir.logfile(), "DEBUG still in the logfile\n");
+
+    drop(logger);
+}
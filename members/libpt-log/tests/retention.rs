@@ -0,0 +1,54 @@
+use std::{
+    fs::File,
+    time::{Duration, SystemTime},
+};
+
+use libpt_log::{info, Logger};
+
+mod common;
+use common::TestDir;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[test]
+fn old_logfiles_are_deleted() {
+    let dir = TestDir::new("retention");
+    std::fs::create_dir_all(&dir).unwrap();
+    let now = SystemTime::now();
+    let old = |name: &str, age: Duration| {
+        let file = File::create(dir.join(name)).unwrap();
+        file.set_modified(now - age).unwrap();
+    };
+    old("app_2020-01-01.log", 100 * DAY);
+    old("app_2020-01-02.log", 5 * DAY);
+    old("app_2020-01-03.log", 4 * DAY);
+    old("app_2020-01-04.log.1", 3 * DAY);
+    old("app_2020-01-05.log", 2 * DAY);
+    // these don't belong to the logger
+    old("other_2020-01-01.log", 100 * DAY);
+    old("app_notes.log", 100 * DAY);
+    old("app_2020-01-01.txt", 100 * DAY);
+
+    let _logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .logfile_template("app_{date}.log")
+        .max_logfiles(3)
+        .max_logfile_age(30 * DAY)
+        .build()
+        .unwrap();
+    info!("cleaned up");
+
+    let files = dir.files();
+    let today = format!("app_{}.log", chrono::Local::now().date_naive());
+    let mut expected = vec![
+        "app_2020-01-01.txt".to_string(),
+        "app_2020-01-04.log.1".to_string(),
+        "app_2020-01-05.log".to_string(),
+        "app_notes.log".to_string(),
+        "other_2020-01-01.log".to_string(),
+        today,
+    ];
+    expected.sort();
+    assert_eq!(files, expected);
+}
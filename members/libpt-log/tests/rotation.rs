@@ -0,0 +1,48 @@
+use libpt_log::{info, Logger, Rotation};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn rotate_by_size() {
+    let dir = TestDir::new("rotation");
+    let _logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .ansi(false)
+        .rotation(Rotation::Size {
+            max_bytes: 256,
+            keep: 2,
+        })
+        .build()
+        .unwrap();
+
+    for i in 0..64 {
+        info!("this is message number {i}");
+    }
+
+    let files: Vec<_> = dir.files().iter().map(|file| dir.join(file)).collect();
+    assert_eq!(files.len(), 3, "{files:?}");
+    assert!(files[1].to_string_lossy().ends_with(".log.1"));
+    assert!(files[2].to_string_lossy().ends_with(".log.2"));
+    for file in &files {
+        assert!(std::fs::metadata(file).unwrap().len() <= 256);
+    }
+    let newest = std::fs::read_to_string(&files[0]).unwrap();
+    assert!(newest.contains("this is message number 63"));
+}
+
+#[test]
+fn time_based_rotation_needs_date() {
+    let dir = TestDir::new("rotation-date");
+    for rotation in [Rotation::Daily, Rotation::Hourly] {
+        let result = Logger::builder()
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .logfile_template("app.log")
+            .rotation(rotation)
+            .build_scoped();
+        assert!(result.is_err(), "{rotation:?}");
+    }
+    assert!(!dir.exists());
+}
@@ -0,0 +1,72 @@
+use libpt_log::{debug, error, info, Level, LevelFilter, Logger};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn build_scoped_twice() {
+    let first = TestDir::new("scoped-first");
+    let second = TestDir::new("scoped-second");
+    {
+        let _logger = Logger::builder()
+            .log_to_file(true)
+            .log_dir(first.path())
+            .build_scoped()
+            .unwrap();
+        info!("to the first");
+        debug!("too verbose for the first");
+    }
+    info!("to nobody");
+    {
+        let _logger = Logger::builder()
+            .log_to_file(true)
+            .log_dir(second.path())
+            .set_level(Level::DEBUG)
+            .build_scoped()
+            .unwrap();
+        debug!("to the second");
+    }
+
+    let first_log = first.logfile();
+    assert!(first_log.contains("to the first"));
+    assert!(!first_log.contains("too verbose"));
+    assert!(!first_log.contains("to nobody"));
+    let second_log = second.logfile();
+    assert!(second_log.contains("to the second"));
+    assert!(!second_log.contains("to the first"));
+}
+
+#[test]
+fn scope_closure() {
+    let dir = TestDir::new("scoped-closure");
+    let answer = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .non_blocking(true)
+        .scope(|| {
+            info!("inside the closure");
+            42
+        })
+        .unwrap();
+    assert_eq!(answer, 42);
+    assert!(dir.logfile().contains("inside the closure"));
+}
+
+#[test]
+fn level_off() {
+    let dir = TestDir::new("scoped-off");
+    {
+        let logger = Logger::builder()
+            .log_to_file(true)
+            .log_dir(dir.path())
+            .set_level(LevelFilter::OFF)
+            .build_scoped()
+            .unwrap();
+        error!("silenced");
+        logger.set_level(Level::ERROR).unwrap();
+        error!("heard again");
+    }
+    let log = dir.logfile();
+    assert!(!log.contains("silenced"));
+    assert!(log.contains("heard again"));
+}
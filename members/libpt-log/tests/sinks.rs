@@ -0,0 +1,41 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{debug, info, trace, tracing::info_span, Level, Logger};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn terminal_and_file_differ() {
+    let dir = TestDir::new("sinks");
+    let _logger = Logger::builder()
+        .set_level(Level::INFO)
+        .display_time(false)
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .file_level(Level::TRACE)
+        .file_display_target(true)
+        .build()
+        .unwrap();
+
+    let out = get_stdout_for!({
+        info!("for everyone");
+        debug!("for the logfile");
+        trace!("also for the logfile");
+        info_span!("req", id = 7).in_scope(|| info!("in a span"));
+    });
+    assert!(out.contains("for everyone"));
+    assert!(out.contains('\x1b'), "{out:?}");
+    assert!(!out.contains("for the logfile"));
+    assert!(!out.contains("sinks:"));
+
+    let content = dir.logfile();
+    assert!(content.contains("INFO sinks: for everyone"), "{content:?}");
+    assert!(content.contains("DEBUG sinks: for the logfile"));
+    assert!(content.contains("TRACE sinks: also for the logfile"));
+    // the terminal formats the fields of the span first, with colors
+    assert!(
+        content.contains("req{id=7}: sinks: in a span"),
+        "{content:?}"
+    );
+    assert!(!content.contains('\x1b'), "{content:?}");
+}
@@ -0,0 +1,41 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{error, info, warn, Logger, TerminalStream};
+
+/// catches what the expression would write to the `stderr`, like [`get_stdout_for!`]
+macro_rules! get_stderr_for {
+    ($test:expr) => {{
+        use gag::BufferRedirect;
+        use std::io::Read;
+
+        let mut buf = BufferRedirect::stderr().unwrap();
+
+        $test;
+
+        let mut output = String::new();
+        buf.read_to_string(&mut output).unwrap();
+        drop(buf);
+
+        output
+    }};
+}
+
+#[test]
+fn warnings_to_stderr() {
+    let _logger = Logger::builder()
+        .terminal_stream(TerminalStream::WARNINGS_TO_STDERR)
+        .ansi(false)
+        .display_time(false)
+        .build()
+        .unwrap();
+
+    let stdout;
+    let stderr = get_stderr_for!({
+        stdout = get_stdout_for!({
+            info!("for the pipe");
+            warn!("careful");
+            error!("broken");
+        });
+    });
+    assert_eq!(stdout, " INFO for the pipe\n");
+    assert_eq!(stderr, " WARN careful\nERROR broken\n");
+}
@@ -0,0 +1,87 @@
+#![cfg(unix)]
+use std::{os::unix::net::UnixDatagram, time::Duration};
+
+use libpt_log::{debug, error, info, warn, Facility, Level, Logger, SyslogFormat};
+
+mod common;
+use common::TestDir;
+
+fn socket(name: &str) -> (TestDir, UnixDatagram) {
+    let dir = TestDir::new(&format!("syslog-{name}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    let socket = UnixDatagram::bind(dir.join("log.sock")).unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    (dir, socket)
+}
+
+fn recv(socket: &UnixDatagram) -> String {
+    let mut buf = vec![0; 4096];
+    let len = socket.recv(&mut buf).unwrap();
+    String::from_utf8(buf[..len].to_vec()).unwrap()
+}
+
+#[test]
+fn rfc5424() {
+    let (dir, socket) = socket("rfc5424");
+    {
+        let _logger = Logger::builder()
+            .log_to_syslog(true)
+            .syslog_path(dir.join("log.sock"))
+            .syslog_app_name("myapp")
+            .build_scoped()
+            .unwrap();
+        info!("hello syslog");
+        debug!("too verbose");
+        error!(answer = 42, "it broke");
+    }
+
+    let pid = std::process::id();
+    let hello = recv(&socket);
+    // user facility (1) and informational severity (6)
+    assert!(hello.starts_with("<14>1 "), "{hello:?}");
+    assert!(hello.contains(&format!(" myapp {pid} - - ")), "{hello:?}");
+    assert!(hello.ends_with("hello syslog"), "{hello:?}");
+    let broke = recv(&socket);
+    // user facility (1) and error severity (3)
+    assert!(broke.starts_with("<11>1 "), "{broke:?}");
+    assert!(broke.ends_with("it broke answer=42"), "{broke:?}");
+}
+
+#[test]
+fn rfc3164() {
+    let (dir, socket) = socket("rfc3164");
+    {
+        let _logger = Logger::builder()
+            .set_level(Level::DEBUG)
+            .log_to_syslog(true)
+            .syslog_path(dir.join("log.sock"))
+            .syslog_format(SyslogFormat::Rfc3164)
+            .syslog_facility(Facility::Local0)
+            .syslog_level(Level::WARN)
+            .syslog_app_name("myapp")
+            .build_scoped()
+            .unwrap();
+        info!("below the syslog level");
+        warn!("careful");
+    }
+
+    let careful = recv(&socket);
+    // local0 facility (16) and warning severity (4)
+    assert!(careful.starts_with("<132>"), "{careful:?}");
+    assert!(
+        careful.ends_with(&format!(" myapp[{}]: careful", std::process::id())),
+        "{careful:?}"
+    );
+}
+
+#[test]
+fn no_daemon() {
+    let path = std::env::temp_dir().join(format!("libpt-log-syslog-none-{}", std::process::id()));
+    assert!(Logger::builder()
+        .log_to_syslog(true)
+        .syslog_path(path)
+        .build_scoped()
+        .is_err());
+}
@@ -0,0 +1,76 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{info, tracing::info_span, warn, LogFormat, Logger, TerminalStream};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn nested_spans() {
+    let _logger = Logger::builder()
+        .terminal_format(LogFormat::Tree)
+        .ansi(false)
+        .display_time(false)
+        .build()
+        .unwrap();
+
+    let stdout = get_stdout_for!({
+        info_span!("download", url = "https://example.com").in_scope(|| {
+            info!("connecting");
+            let retry = info_span!("retry", attempt = 2);
+            retry.in_scope(|| warn!(after = 5, "timed out"));
+            // the fields are only shown the first time the span is entered
+            retry.in_scope(|| info!("done"));
+        });
+        info!("outside");
+    });
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 8, "{stdout}");
+    assert_eq!(
+        lines[..5],
+        [
+            "┌─ download url=\"https://example.com\"",
+            "│  INFO  connecting",
+            "│  ┌─ retry attempt=2",
+            "│  │  WARN  timed out after=5",
+            "│  │  INFO  done",
+        ]
+    );
+    // the elapsed time differs between runs
+    assert!(lines[5].starts_with("│  └─ retry "), "{stdout}");
+    assert!(lines[6].starts_with("└─ download "), "{stdout}");
+    assert!(lines[6].ends_with('s'), "{stdout}");
+    assert_eq!(lines[7], "INFO  outside");
+}
+
+#[test]
+fn tree_on_two_sinks() {
+    let dir = TestDir::new("tree");
+    // stdout is captured by the other test
+    let logger = Logger::builder()
+        .terminal_format(LogFormat::Tree)
+        .terminal_stream(TerminalStream::Stderr)
+        .file_format(LogFormat::Tree)
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .ansi(false)
+        .display_time(false)
+        .build_scoped()
+        .unwrap();
+
+    info_span!("outer", n = 1).in_scope(|| {
+        info_span!("inner").in_scope(|| info!("deep"));
+    });
+    logger.flush().unwrap();
+
+    let content = dir.logfile();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 5, "{content}");
+    assert_eq!(
+        lines[..3],
+        ["┌─ outer n=1", "│  ┌─ inner", "│  │  INFO  deep"]
+    );
+    assert!(lines[3].starts_with("│  └─ inner "), "{content}");
+    assert!(lines[4].starts_with("└─ outer "), "{content}");
+
+    drop(logger);
+}
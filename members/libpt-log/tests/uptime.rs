@@ -0,0 +1,13 @@
+use libpt_core::get_stdout_for;
+use libpt_log::{info, Logger};
+
+#[test]
+fn uptime() {
+    let _logger = Logger::builder().ansi(false).uptime(true).build().unwrap();
+
+    let out = get_stdout_for!(info!("hello world"));
+    let (timestamp, rest) = out.trim_start().split_once(' ').unwrap();
+    let seconds: f64 = timestamp.strip_suffix('s').unwrap().parse().unwrap();
+    assert!(seconds < 10.0, "{out}");
+    assert_eq!(rest.trim_start(), "INFO hello world\n");
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "zstd")]
+use libpt_log::{info, Compression, Logger, Rotation};
+
+mod common;
+use common::TestDir;
+
+#[test]
+fn rotated_logfiles_are_compressed() {
+    let dir = TestDir::new("zstd");
+
+    let logger = Logger::builder()
+        .log_to_file(true)
+        .log_dir(dir.path())
+        .logfile_template("app.log")
+        .ansi(false)
+        .rotation(Rotation::Size {
+            max_bytes: 256,
+            keep: 2,
+        })
+        .compression(Compression::Zstd)
+        .build()
+        .unwrap();
+
+    for i in 0..64 {
+        info!("this is message number {i}");
+    }
+    logger.flush().unwrap();
+
+    let files = dir.files();
+    assert_eq!(files, ["app.log", "app.log.1.zst", "app.log.2.zst"]);
+
+    let compressed = std::fs::read(dir.join("app.log.1.zst")).unwrap();
+    let content = String::from_utf8(zstd::decode_all(compressed.as_slice()).unwrap()).unwrap();
+    assert!(content.contains("this is message number"));
+}